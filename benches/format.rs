@@ -0,0 +1,120 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_decimal::prelude::*;
+use rusty_money::{iso, Money};
+use separator::Separatable;
+
+use dec_utils::{dec_to_separated_string, dec_to_usd_string, Separated, Usd};
+
+// The functions as they were before `DecimalFormatter`, the baseline the
+// improvement is measured against
+mod baseline {
+    use super::*;
+
+    pub fn dec_to_usd_string(v: Decimal) -> String {
+        let v_string = v.round_dp(2).to_string();
+        match Money::from_str(&v_string, iso::USD) {
+            Ok(v) => format!("{}", v),
+            Err(e) => format!("({} {})", v_string, e),
+        }
+    }
+
+    pub fn dec_to_separated_string(v: Decimal, dp: u32) -> String {
+        let negative = v.is_sign_negative();
+        let rounded = v.abs().round_dp(dp);
+        let integral_part = rounded.trunc();
+        let fractional_part_string = rounded.fract().to_string();
+        let fractional_part_str = if dp == 0 {
+            ""
+        } else {
+            std::str::from_utf8(&fractional_part_string.as_bytes()[1..]).unwrap()
+        };
+        format!(
+            "{}{}{}",
+            if negative { "-" } else { "" },
+            integral_part.to_u128().unwrap().separated_string(),
+            fractional_part_str,
+        )
+    }
+}
+
+// Values like a column of a report, a mix of magnitudes and signs
+fn values() -> Vec<Decimal> {
+    (0..1_000i64)
+        .map(|i| Decimal::new((i * 7_919_573 - 3_000_000) * (i % 7 + 1), (i % 5) as u32))
+        .collect()
+}
+
+fn bench_separated(c: &mut Criterion) {
+    let values = values();
+    let mut g = c.benchmark_group("separated");
+    g.bench_function("baseline", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for v in &values {
+                total += baseline::dec_to_separated_string(black_box(*v), 2).len();
+            }
+            total
+        })
+    });
+    g.bench_function("dec_to_separated_string", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for v in &values {
+                total += dec_to_separated_string(black_box(*v), 2).len();
+            }
+            total
+        })
+    });
+    g.bench_function("Separated write_to", |b| {
+        let mut out = String::with_capacity(64);
+        b.iter(|| {
+            let mut total = 0;
+            for v in &values {
+                out.clear();
+                Separated(black_box(v), 2).write_to(&mut out).unwrap();
+                total += out.len();
+            }
+            total
+        })
+    });
+    g.finish();
+}
+
+fn bench_usd(c: &mut Criterion) {
+    let values = values();
+    let mut g = c.benchmark_group("usd");
+    g.bench_function("baseline", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for v in &values {
+                total += baseline::dec_to_usd_string(black_box(*v)).len();
+            }
+            total
+        })
+    });
+    g.bench_function("dec_to_usd_string", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for v in &values {
+                total += dec_to_usd_string(black_box(*v)).len();
+            }
+            total
+        })
+    });
+    g.bench_function("Usd write_to", |b| {
+        let mut out = String::with_capacity(64);
+        b.iter(|| {
+            let mut total = 0;
+            for v in &values {
+                out.clear();
+                Usd(black_box(v)).write_to(&mut out).unwrap();
+                total += out.len();
+            }
+            total
+        })
+    });
+    g.finish();
+}
+
+criterion_group!(benches, bench_separated, bench_usd);
+criterion_main!(benches);
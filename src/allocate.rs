@@ -0,0 +1,398 @@
+use rust_decimal::prelude::*;
+
+use crate::DecUtilsError;
+
+/// Which parts receive the units left over after every part is rounded
+/// toward zero, see `allocate_with_strategy`
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{allocate_with_strategy, RemainderStrategy};
+///
+/// let ratios = [dec!(0.5), dec!(0.3), dec!(0.2)];
+/// let parts = |strategy| allocate_with_strategy(dec!(0.99), &ratios, 2, strategy);
+/// assert_eq!(
+///     parts(RemainderStrategy::LargestRemainder),
+///     Ok(vec![dec!(0.49), dec!(0.30), dec!(0.20)])
+/// );
+/// assert_eq!(
+///     parts(RemainderStrategy::FirstN),
+///     Ok(vec![dec!(0.50), dec!(0.30), dec!(0.19)])
+/// );
+/// assert_eq!(
+///     parts(RemainderStrategy::RoundRobin { start: 2 }),
+///     Ok(vec![dec!(0.50), dec!(0.29), dec!(0.20)])
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RemainderStrategy {
+    /// The parts whose exact shares were reduced the most by rounding,
+    /// ties go to the earlier part. This keeps every part closest to its
+    /// exact share.
+    #[default]
+    LargestRemainder,
+
+    /// The first parts in order
+    FirstN,
+
+    /// The parts in order starting at part `start` and wrapping around,
+    /// e.g. pass the period number so the same account doesn't always
+    /// receive the extra cent
+    RoundRobin { start: usize },
+}
+
+/// Allocate `total` in proportion to `ratios` with "largest remainder"
+/// distribution, see `allocate_with_strategy`
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::allocate;
+///
+/// let parts = allocate(dec!(100), &[dec!(1), dec!(1), dec!(1)], 2).unwrap();
+/// assert_eq!(parts, [dec!(33.34), dec!(33.33), dec!(33.33)]);
+/// assert_eq!(parts.iter().sum::<rust_decimal::Decimal>(), dec!(100));
+/// ```
+pub fn allocate(
+    total: Decimal,
+    ratios: &[Decimal],
+    dp: u32,
+) -> Result<Vec<Decimal>, DecUtilsError> {
+    allocate_with_strategy(total, ratios, dp, RemainderStrategy::default())
+}
+
+/// Allocate `total` in proportion to `ratios`, one part per ratio with
+/// at most `dp` decimal places, and the parts sum exactly to `total`
+///
+/// Each part is its exact share rounded toward zero, then the units of
+/// 10^-`dp` left over are given one each to parts chosen by `strategy`.
+/// Parts with a zero ratio are always zero. A negative total gives
+/// negative parts.
+///
+/// `dp` is at most 28 and `total` must be a whole number of units of
+/// 10^-`dp` that a `Decimal` can hold, |`total`| × 10^`dp` at most
+/// 2^96 - 1, so parts with `dp` decimal places can't round. E.g. 10^26
+/// can be split to 2 decimal places but 10^27 only to 1.
+///
+/// Returns `DecUtilsError::InvalidAllocation` if `ratios` is empty, has
+/// a negative ratio or sums to zero, or if `total` has more than `dp`
+/// decimal places or too many digits so it can't be split exactly.
+/// Returns `DecUtilsError::Overflow` if the ratios sum is too large.
+pub fn allocate_with_strategy(
+    total: Decimal,
+    ratios: &[Decimal],
+    dp: u32,
+    strategy: RemainderStrategy,
+) -> Result<Vec<Decimal>, DecUtilsError> {
+    let invalid = |reason: &str| Err(DecUtilsError::InvalidAllocation(reason.to_owned()));
+    // A Decimal never has more than 28 decimal places
+    let dp = dp.min(28);
+    if ratios.is_empty() {
+        return invalid("no ratios");
+    }
+    if ratios.iter().any(|r| r.is_sign_negative() && !r.is_zero()) {
+        return invalid("negative ratio");
+    }
+    let sum = ratios
+        .iter()
+        .try_fold(Decimal::ZERO, |sum, r| sum.checked_add(*r))
+        .ok_or(DecUtilsError::Overflow)?;
+    if sum.is_zero() {
+        return invalid("ratios sum to zero");
+    }
+    if total.round_dp(dp) != total {
+        return invalid(&format!(
+            "total {} has more than {} decimal places",
+            total, dp
+        ));
+    }
+
+    // Parts are computed as whole units of 10^-dp, the count of units in
+    // the total must fit the 96 bit mantissa of a Decimal
+    let magnitude = total.abs();
+    let units = to_units(magnitude, dp)
+        .filter(|&units| units <= Decimal::MAX.mantissa())
+        .ok_or_else(|| {
+            DecUtilsError::InvalidAllocation(format!(
+                "total {} has too many digits to split with {} decimal places",
+                total, dp
+            ))
+        })?;
+
+    // A fraction is at most 1, so a share is at most the magnitude and
+    // never overflows. Shares are only exact to 28 significant digits,
+    // the parts are clamped so they never add up to more than the total.
+    let shares: Vec<Decimal> = ratios
+        .iter()
+        .map(|r| magnitude * (r / sum).min(Decimal::ONE))
+        .collect();
+    let mut rest = units;
+    let mut part_units: Vec<i128> = shares
+        .iter()
+        .map(|&share| {
+            let part = to_units(share, dp).unwrap_or(rest).min(rest);
+            rest -= part;
+            part
+        })
+        .collect();
+
+    let mut receivers: Vec<usize> = (0..ratios.len())
+        .filter(|&i| !ratios[i].is_zero())
+        .collect();
+    match strategy {
+        RemainderStrategy::LargestRemainder => {
+            let remainder = |i: usize| shares[i] - Decimal::from_i128_with_scale(part_units[i], dp);
+            receivers.sort_by_key(|&i| std::cmp::Reverse(remainder(i)));
+        }
+        RemainderStrategy::FirstN => {}
+        RemainderStrategy::RoundRobin { start } => {
+            let start = start % ratios.len();
+            receivers.sort_by_key(|&i| (i + ratios.len() - start) % ratios.len());
+        }
+    }
+
+    // With inexact shares the leftover can be more units than there are
+    // receivers, every receiver gets an equal number of them at once and
+    // the fewer than n that remain are given one each
+    let n = receivers.len() as i128;
+    let each = rest / n;
+    for (k, &i) in receivers.iter().enumerate() {
+        part_units[i] += each + i128::from((k as i128) < rest % n);
+    }
+    let mut parts: Vec<Decimal> = part_units
+        .into_iter()
+        .map(|units| Decimal::from_i128_with_scale(units, dp))
+        .collect();
+
+    if total.is_sign_negative() {
+        for part in &mut parts {
+            *part = -*part;
+        }
+    }
+    Ok(parts)
+}
+
+/// Split `total` into `n` parts as equal as possible, earlier parts
+/// receive the leftover units, see `split_evenly_with_strategy`
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::split_evenly;
+///
+/// assert_eq!(
+///     split_evenly(dec!(-10), 3, 2),
+///     Ok(vec![dec!(-3.34), dec!(-3.33), dec!(-3.33)])
+/// );
+/// ```
+pub fn split_evenly(total: Decimal, n: usize, dp: u32) -> Result<Vec<Decimal>, DecUtilsError> {
+    split_evenly_with_strategy(total, n, dp, RemainderStrategy::default())
+}
+
+/// Split `total` into `n` parts with at most `dp` decimal places that
+/// differ by at most one unit of 10^-`dp` and sum exactly to `total`,
+/// with the leftover units given to parts chosen by `strategy`
+///
+/// Returns `DecUtilsError::InvalidAllocation` if `n` is zero or `total`
+/// can't be split exactly, see `allocate_with_strategy`.
+pub fn split_evenly_with_strategy(
+    total: Decimal,
+    n: usize,
+    dp: u32,
+    strategy: RemainderStrategy,
+) -> Result<Vec<Decimal>, DecUtilsError> {
+    allocate_with_strategy(total, &vec![Decimal::ONE; n], dp, strategy)
+}
+
+/// `v` rounded toward zero to `dp` decimal places as a count of units of
+/// 10^-`dp`, None if that's too large for an i128
+fn to_units(v: Decimal, dp: u32) -> Option<i128> {
+    let v = v.round_dp_with_strategy(dp, RoundingStrategy::ToZero);
+    10i128
+        .checked_pow(dp - v.scale())
+        .and_then(|scale| v.mantissa().checked_mul(scale))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    // The exact sum of `parts` in units of 10^-`dp`, summing the
+    // decimals would round
+    fn units_sum(parts: &[Decimal], dp: u32) -> Option<i128> {
+        parts.iter().map(|&part| to_units(part, dp)).sum()
+    }
+
+    const STRATEGIES: [RemainderStrategy; 4] = [
+        RemainderStrategy::LargestRemainder,
+        RemainderStrategy::FirstN,
+        RemainderStrategy::RoundRobin { start: 0 },
+        RemainderStrategy::RoundRobin { start: 5 },
+    ];
+
+    #[test]
+    fn test_parts_sum_to_total() {
+        let totals = [
+            dec!(0),
+            dec!(0.01),
+            dec!(-0.05),
+            dec!(100),
+            dec!(1234.57),
+            dec!(-99999.99),
+            // The most units of 0.01 a Decimal holds
+            Decimal::from_i128_with_scale(Decimal::MAX.mantissa(), 2),
+            Decimal::from_i128_with_scale(Decimal::MIN.mantissa(), 2),
+        ];
+        let ratios: [&[Decimal]; 6] = [
+            &[dec!(1)],
+            &[dec!(1), dec!(1), dec!(1)],
+            &[dec!(0.5), dec!(0.3), dec!(0.2)],
+            &[dec!(0), dec!(7), dec!(0), dec!(3)],
+            &[
+                dec!(1),
+                dec!(2),
+                dec!(3),
+                dec!(4),
+                dec!(5),
+                dec!(6),
+                dec!(7),
+            ],
+            &[
+                dec!(0.0000001),
+                dec!(1000000000),
+                dec!(0.333333333333333333),
+            ],
+        ];
+        for total in totals {
+            for ratios in ratios {
+                let sum: Decimal = ratios.iter().sum();
+                for strategy in STRATEGIES {
+                    let parts = allocate_with_strategy(total, ratios, 2, strategy).unwrap();
+                    assert_eq!(parts.len(), ratios.len());
+                    assert_eq!(units_sum(&parts, 2), to_units(total, 2));
+                    for (part, ratio) in parts.iter().zip(ratios) {
+                        assert_eq!(part.round_dp(2), *part);
+                        if ratio.is_zero() {
+                            assert!(part.is_zero());
+                        }
+                        // Every part is within one unit of its exact share,
+                        // shares of the largest totals are only accurate to
+                        // 28 significant digits
+                        if total.abs() < dec!(1e20) {
+                            let share = total.abs() * (ratio / sum);
+                            assert!((part.abs() - share).abs() <= dec!(0.01));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_allocate() {
+        assert_eq!(
+            allocate(dec!(0.05), &[dec!(3), dec!(7)], 2),
+            Ok(vec![dec!(0.02), dec!(0.03)])
+        );
+        assert_eq!(
+            allocate(dec!(1000), &[dec!(0.7), dec!(0.2), dec!(0.1)], 2),
+            Ok(vec![dec!(700), dec!(200), dec!(100)])
+        );
+        assert_eq!(
+            allocate(dec!(0.001), &[dec!(1), dec!(1)], 3),
+            Ok(vec![dec!(0.001), dec!(0)])
+        );
+        assert_eq!(
+            allocate_with_strategy(
+                dec!(0.01),
+                &[dec!(0), dec!(1), dec!(1)],
+                2,
+                RemainderStrategy::FirstN
+            ),
+            Ok(vec![dec!(0), dec!(0.01), dec!(0)])
+        );
+    }
+
+    #[test]
+    fn test_split_evenly() {
+        assert_eq!(
+            split_evenly(dec!(100), 3, 2),
+            Ok(vec![dec!(33.34), dec!(33.33), dec!(33.33)])
+        );
+        assert_eq!(
+            split_evenly_with_strategy(
+                dec!(0.05),
+                3,
+                2,
+                RemainderStrategy::RoundRobin { start: 2 }
+            ),
+            Ok(vec![dec!(0.02), dec!(0.01), dec!(0.02)])
+        );
+        assert_eq!(
+            split_evenly(dec!(1), 4, 0),
+            Ok(vec![dec!(1), dec!(0), dec!(0), dec!(0)])
+        );
+        for n in 1..=12 {
+            for total in [dec!(0.07), dec!(10), dec!(-123.45)] {
+                let parts = split_evenly(total, n, 2).unwrap();
+                assert_eq!(units_sum(&parts, 2), to_units(total, 2));
+                let max = parts.iter().max().unwrap();
+                let min = parts.iter().min().unwrap();
+                assert!(max - min <= dec!(0.01));
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_totals() {
+        // Shares of these totals are inexact, the parts still add up to
+        // exactly the total
+        let ratios = [dec!(0.3333333), dec!(1), dec!(17)];
+        for strategy in STRATEGIES {
+            let parts = allocate_with_strategy(dec!(1e27), &ratios, 1, strategy).unwrap();
+            assert_eq!(units_sum(&parts, 1), to_units(dec!(1e27), 1));
+
+            let parts = split_evenly_with_strategy(dec!(100000000), 3, 20, strategy).unwrap();
+            assert_eq!(units_sum(&parts, 20), to_units(dec!(100000000), 20));
+            let max = parts.iter().max().unwrap();
+            let min = parts.iter().min().unwrap();
+            assert!(max - min <= Decimal::new(1, 20));
+        }
+        let parts = allocate(Decimal::MAX, &[dec!(1), dec!(2), dec!(4)], 0).unwrap();
+        assert_eq!(units_sum(&parts, 0), Some(Decimal::MAX.mantissa()));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let invalid = |reason: &str| Err(DecUtilsError::InvalidAllocation(reason.to_owned()));
+        assert_eq!(allocate(dec!(1), &[], 2), invalid("no ratios"));
+        assert_eq!(split_evenly(dec!(1), 0, 2), invalid("no ratios"));
+        assert_eq!(
+            allocate(dec!(1), &[dec!(1), dec!(-1)], 2),
+            invalid("negative ratio")
+        );
+        assert_eq!(
+            allocate(dec!(1), &[dec!(0), dec!(0)], 2),
+            invalid("ratios sum to zero")
+        );
+        assert_eq!(
+            split_evenly(dec!(1.005), 2, 2),
+            invalid("total 1.005 has more than 2 decimal places")
+        );
+        assert_eq!(
+            allocate(dec!(1), &[Decimal::MAX, Decimal::MAX], 2),
+            Err(DecUtilsError::Overflow)
+        );
+        assert_eq!(
+            allocate(dec!(1e27), &[dec!(1), dec!(17)], 2),
+            invalid("total 1000000000000000000000000000 has too many digits to split with 2 decimal places")
+        );
+        assert!(split_evenly(Decimal::MAX, 3, 28).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use rust_decimal::prelude::*;
+
+use crate::rounding::msd_exponent;
+use crate::{DecUtilsError, DecimalFormatter, RoundingMode};
+
+/// The set of unit suffixes used by compact formatting
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CompactScale {
+    /// K, M, B and T for 10^3, 10^6, 10^9 and 10^12
+    #[default]
+    Short,
+
+    /// K, M, Md, B, Bd and T for 10^3, 10^6, 10^9, 10^12, 10^15 and 10^18
+    Long,
+
+    /// K, L and Cr for thousand, lakh (10^5) and crore (10^7)
+    Indian,
+
+    /// k, M, G, T, P and E for 10^3 through 10^18
+    Si,
+}
+
+impl CompactScale {
+    /// The (exponent, suffix) pairs of the scale in ascending order
+    pub fn units(self) -> &'static [(u32, &'static str)] {
+        match self {
+            CompactScale::Short => &[(3, "K"), (6, "M"), (9, "B"), (12, "T")],
+            CompactScale::Long => &[
+                (3, "K"),
+                (6, "M"),
+                (9, "Md"),
+                (12, "B"),
+                (15, "Bd"),
+                (18, "T"),
+            ],
+            CompactScale::Indian => &[(3, "K"), (5, "L"), (7, "Cr")],
+            CompactScale::Si => &[
+                (3, "k"),
+                (6, "M"),
+                (9, "G"),
+                (12, "T"),
+                (15, "P"),
+                (18, "E"),
+            ],
+        }
+    }
+}
+
+/// Formats decimals abbreviated with a unit suffix, e.g. "1.2K", "34.5M"
+///
+/// The value is divided by the largest unit not greater than it and
+/// rounded to `significant_digits`, trailing fractional zeros are
+/// removed. Integral digits are never rounded away, so large values
+/// keep all of their digits before the last unit.
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{CompactFormatter, CompactScale, DecimalFormatter, RoundingMode};
+///
+/// let f = CompactFormatter::new();
+/// assert_eq!(f.format(dec!(1234)), "1.23K");
+/// assert_eq!(f.format(dec!(34_512_345)), "34.5M");
+/// assert_eq!(f.format(dec!(-999.9)), "-1K");
+///
+/// let f = f.significant_digits(2).base(DecimalFormatter::usd());
+/// assert_eq!(f.format(dec!(2_149_000_000)), "$2.1B");
+///
+/// let f = CompactFormatter::new()
+///     .scale(CompactScale::Indian)
+///     .rounding(RoundingMode::ToZero);
+/// assert_eq!(f.format(dec!(12_345_678)), "1.23Cr");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactFormatter {
+    significant_digits: u32,
+    rounding: RoundingMode,
+    scale: CompactScale,
+    base: DecimalFormatter,
+}
+
+impl Default for CompactFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompactFormatter {
+    /// A formatter with 3 significant digits, "Bankers Rounding", the
+    /// short scale and the separators of `DecimalFormatter::separated`
+    pub fn new() -> Self {
+        Self {
+            significant_digits: 3,
+            rounding: RoundingMode::default(),
+            scale: CompactScale::default(),
+            base: DecimalFormatter::separated(0),
+        }
+    }
+
+    /// Number of significant digits to round to, at least 1
+    pub fn significant_digits(mut self, significant_digits: u32) -> Self {
+        self.significant_digits = significant_digits.max(1);
+        self
+    }
+
+    /// The mode used when rounding to the significant digits
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// The set of unit suffixes
+    pub fn scale(mut self, scale: CompactScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The formatter supplying the separators, sign style, prefix and
+    /// suffix, its decimal places and rounding are replaced
+    pub fn base(mut self, base: DecimalFormatter) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Format `v`, see `DecimalFormatter::format` for the fallback
+    /// used if the base formatter is invalid
+    pub fn format(&self, v: Decimal) -> String {
+        self.try_format(v).unwrap_or_else(|_| v.to_string())
+    }
+
+    /// Format `v` or return an error if the base formatter is invalid
+    pub fn try_format(&self, v: Decimal) -> Result<String, DecUtilsError> {
+        let units = self.scale.units();
+        let abs = v.abs();
+        let mut unit = units.iter().rposition(|&(exp, _)| abs >= pow10(exp));
+
+        loop {
+            let exp = unit.map_or(0, |u| units[u].0);
+            let scaled = v / pow10(exp);
+            // Values below 1 keep their significant digits too, e.g.
+            // 0.0456 to 3 digits needs 4 decimal places
+            let msd = msd_exponent(scaled).unwrap_or(0);
+            let dp = (self.significant_digits as i32 - 1 - msd).clamp(0, 28) as u32;
+            let rounded = self.rounding.round_dp(scaled, dp);
+
+            // Rounding up may reach the next unit, e.g. 999.9K is 1M
+            let next = unit.map_or(0, |u| u + 1);
+            if let Some(&(next_exp, _)) = units.get(next) {
+                if rounded.abs() >= pow10(next_exp - exp) {
+                    unit = Some(next);
+                    continue;
+                }
+            }
+
+            let suffix = unit.map_or("", |u| units[u].1);
+            return self
+                .base
+                .clone()
+                .fraction_digits(0, dp)
+                .rounding(self.rounding)
+                .unit(suffix)
+                .try_format(rounded);
+        }
+    }
+}
+
+fn pow10(exp: u32) -> Decimal {
+    Decimal::from_i128_with_scale(10i128.pow(exp), 0)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::iso;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_short() {
+        let f = CompactFormatter::new();
+        assert_eq!(f.format(dec!(0)), "0");
+        assert_eq!(f.format(dec!(0.126)), "0.126");
+        assert_eq!(f.format(dec!(999)), "999");
+        assert_eq!(f.format(dec!(999.5)), "1K");
+        assert_eq!(f.format(dec!(1000)), "1K");
+        assert_eq!(f.format(dec!(1050)), "1.05K");
+        assert_eq!(f.format(dec!(999_950)), "1M");
+        assert_eq!(f.format(dec!(-1_234_567_890)), "-1.23B");
+        assert_eq!(f.format(dec!(1_234_567_890_123_456)), "1,235T");
+        assert_eq!(f.format(Decimal::MAX), "79,228,162,514,264,338T");
+    }
+
+    #[test]
+    fn test_below_one() {
+        let f = CompactFormatter::new();
+        assert_eq!(f.format(dec!(0.00123)), "0.00123");
+        assert_eq!(f.format(dec!(-0.045678)), "-0.0457");
+        assert_eq!(f.format(dec!(0.09999)), "0.1");
+        assert_eq!(f.format(dec!(0.5)), "0.5");
+        assert_eq!(f.clone().significant_digits(1).format(dec!(0.0456)), "0.05");
+        assert_eq!(
+            f.significant_digits(2).format(Decimal::new(123, 28)),
+            "0.000000000000000000000000012"
+        );
+    }
+
+    #[test]
+    fn test_scales() {
+        let v = dec!(1_234_567_890_123_456_789);
+        let f = |scale| CompactFormatter::new().scale(scale).format(v);
+        assert_eq!(f(CompactScale::Short), "1,234,568T");
+        assert_eq!(f(CompactScale::Long), "1.23T");
+        assert_eq!(f(CompactScale::Si), "1.23E");
+        assert_eq!(f(CompactScale::Indian), "123,456,789,012Cr");
+
+        let f = CompactFormatter::new().scale(CompactScale::Indian);
+        assert_eq!(f.format(dec!(150_000)), "1.5L");
+        assert_eq!(f.format(dec!(99_999)), "1L");
+        assert_eq!(f.format(dec!(99_999_999)), "10Cr");
+        let f = CompactFormatter::new().scale(CompactScale::Si);
+        assert_eq!(f.format(dec!(4_500)), "4.5k");
+    }
+
+    #[test]
+    fn test_significant_digits_and_rounding() {
+        let v = dec!(34_550_000);
+        let f = CompactFormatter::new();
+        assert_eq!(f.clone().significant_digits(1).format(v), "35M");
+        assert_eq!(f.clone().significant_digits(0).format(v), "35M");
+        assert_eq!(f.clone().format(v), "34.6M");
+        assert_eq!(f.clone().significant_digits(5).format(v), "34.55M");
+        assert_eq!(f.clone().rounding(RoundingMode::ToZero).format(v), "34.5M");
+        assert_eq!(
+            f.rounding(RoundingMode::HALF_DOWN)
+                .format(dec!(-34_450_000)),
+            "-34.4M"
+        );
+    }
+
+    #[test]
+    fn test_base() {
+        let f = CompactFormatter::new().base(DecimalFormatter::usd());
+        assert_eq!(f.format(dec!(-2_100_000_000)), "-$2.1B");
+        let f = CompactFormatter::new().base(DecimalFormatter::money(iso::EUR));
+        assert_eq!(f.format(dec!(1_250)), "€1,25K");
+        let f = CompactFormatter::new().base(DecimalFormatter::money(iso::SEK));
+        assert_eq!(f.format(dec!(1_250)), "1,25K kr");
+        let f = CompactFormatter::new().base(
+            DecimalFormatter::new()
+                .decimal_mark('.')
+                .group_separator(Some('.')),
+        );
+        assert!(f.try_format(dec!(1)).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! Cryptocurrency definitions and a registry of custom tokens
+//!
+//! The built-in currencies are constants, mirroring `rusty_money::iso`,
+//! and may be passed to the money formatters directly.
+//!
+//! # Example
+//! ```
+//! use rust_decimal_macros::dec;
+//!
+//! use dec_utils::crypto::{self, CryptoCurrency, CryptoRegistry};
+//! use dec_utils::dec_to_money_string;
+//!
+//! assert_eq!(dec_to_money_string(dec!(0.123456789), crypto::BTC), "₿0.12345679");
+//! assert_eq!(dec_to_money_string(dec!(1234.5), crypto::USDT), "1,234.50 USDT");
+//!
+//! let mut registry = CryptoRegistry::with_builtins();
+//! registry.register(CryptoCurrency::custom("PEPE", "PEPE", 18, 0, false));
+//! let pepe = registry.find("pepe").unwrap();
+//! assert_eq!(dec_to_money_string(dec!(123456789.5), pepe), "123,456,790 PEPE");
+//! ```
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use rust_decimal::prelude::*;
+
+use crate::{Currency, NumberSymbols, RoundingMode};
+
+/// A cryptocurrency
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CryptoCurrency {
+    ticker: Cow<'static, str>,
+    symbol: Cow<'static, str>,
+    native_precision: u32,
+    display_precision: u32,
+    symbol_first: bool,
+}
+
+impl CryptoCurrency {
+    /// Define a currency with a static ticker and symbol
+    pub const fn new(
+        ticker: &'static str,
+        symbol: &'static str,
+        native_precision: u32,
+        display_precision: u32,
+        symbol_first: bool,
+    ) -> Self {
+        Self {
+            ticker: Cow::Borrowed(ticker),
+            symbol: Cow::Borrowed(symbol),
+            native_precision,
+            display_precision,
+            symbol_first,
+        }
+    }
+
+    /// Define a currency at runtime, e.g. for a new listing
+    pub fn custom(
+        ticker: impl Into<String>,
+        symbol: impl Into<String>,
+        native_precision: u32,
+        display_precision: u32,
+        symbol_first: bool,
+    ) -> Self {
+        Self {
+            ticker: Cow::Owned(ticker.into()),
+            symbol: Cow::Owned(symbol.into()),
+            native_precision,
+            display_precision,
+            symbol_first,
+        }
+    }
+
+    /// The ticker, e.g. "BTC"
+    pub fn ticker(&self) -> &str {
+        &self.ticker
+    }
+
+    /// Number of decimal places of the smallest on-chain unit,
+    /// e.g. 8 for BTC (satoshi) and 18 for ETH (wei)
+    pub fn native_precision(&self) -> u32 {
+        self.native_precision
+    }
+
+    /// Number of decimal places amounts are displayed with
+    pub fn display_precision(&self) -> u32 {
+        self.display_precision
+    }
+
+    /// Round `v` to the native precision using `rounding`
+    pub fn round_native(&self, v: Decimal, rounding: RoundingMode) -> Decimal {
+        rounding.round_dp(v, self.native_precision)
+    }
+}
+
+impl Currency for CryptoCurrency {
+    fn code(&self) -> &str {
+        &self.ticker
+    }
+
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn exponent(&self) -> u32 {
+        self.display_precision
+    }
+
+    fn symbol_first(&self) -> bool {
+        self.symbol_first
+    }
+
+    fn number_symbols(&self) -> NumberSymbols {
+        NumberSymbols::new(Some(','), '.')
+    }
+
+    // Leading crypto symbols such as "Ξ" are letters but never spaced
+    fn symbol_spacing(&self) -> bool {
+        !self.symbol_first
+    }
+}
+
+/// Bitcoin
+pub const BTC: &CryptoCurrency = &CryptoCurrency::new("BTC", "₿", 8, 8, true);
+/// Ether
+pub const ETH: &CryptoCurrency = &CryptoCurrency::new("ETH", "Ξ", 18, 6, true);
+/// Tether
+pub const USDT: &CryptoCurrency = &CryptoCurrency::new("USDT", "USDT", 6, 2, false);
+/// USD Coin
+pub const USDC: &CryptoCurrency = &CryptoCurrency::new("USDC", "USDC", 6, 2, false);
+/// BNB
+pub const BNB: &CryptoCurrency = &CryptoCurrency::new("BNB", "BNB", 18, 4, false);
+/// Solana
+pub const SOL: &CryptoCurrency = &CryptoCurrency::new("SOL", "SOL", 9, 4, false);
+/// XRP
+pub const XRP: &CryptoCurrency = &CryptoCurrency::new("XRP", "XRP", 6, 4, false);
+/// Cardano
+pub const ADA: &CryptoCurrency = &CryptoCurrency::new("ADA", "ADA", 6, 4, false);
+/// Dogecoin
+pub const DOGE: &CryptoCurrency = &CryptoCurrency::new("DOGE", "Ð", 8, 4, true);
+/// Litecoin
+pub const LTC: &CryptoCurrency = &CryptoCurrency::new("LTC", "Ł", 8, 8, true);
+/// Polkadot
+pub const DOT: &CryptoCurrency = &CryptoCurrency::new("DOT", "DOT", 10, 4, false);
+/// Tron
+pub const TRX: &CryptoCurrency = &CryptoCurrency::new("TRX", "TRX", 6, 4, false);
+
+/// All of the built-in currencies
+pub const ALL: [&CryptoCurrency; 12] = [
+    BTC, ETH, USDT, USDC, BNB, SOL, XRP, ADA, DOGE, LTC, DOT, TRX,
+];
+
+/// Find a built-in currency by ticker, case insensitive
+pub fn find(ticker: &str) -> Option<&'static CryptoCurrency> {
+    ALL.into_iter()
+        .find(|c| c.ticker.eq_ignore_ascii_case(ticker))
+}
+
+/// A set of cryptocurrencies looked up by ticker, case insensitive
+#[derive(Clone, Debug, Default)]
+pub struct CryptoRegistry {
+    currencies: HashMap<String, CryptoCurrency>,
+}
+
+impl CryptoRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry containing the built-in currencies
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for c in ALL {
+            registry.register(c.clone());
+        }
+        registry
+    }
+
+    /// Add `currency`, returning the currency it replaced if its
+    /// ticker was already registered
+    pub fn register(&mut self, currency: CryptoCurrency) -> Option<CryptoCurrency> {
+        self.currencies
+            .insert(currency.ticker.to_ascii_uppercase(), currency)
+    }
+
+    /// Remove the currency with `ticker`
+    pub fn unregister(&mut self, ticker: &str) -> Option<CryptoCurrency> {
+        self.currencies.remove(&ticker.to_ascii_uppercase())
+    }
+
+    /// Find the currency with `ticker`
+    pub fn find(&self, ticker: &str) -> Option<&CryptoCurrency> {
+        self.currencies.get(&ticker.to_ascii_uppercase())
+    }
+
+    /// Iterate over the registered currencies in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &CryptoCurrency> {
+        self.currencies.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::dec_to_money_string;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_builtins() {
+        assert_eq!(find("btc"), Some(BTC));
+        assert_eq!(find("eth").unwrap().native_precision(), 18);
+        assert_eq!(find("nope"), None);
+        assert_eq!(dec_to_money_string(dec!(-1.5), ETH), "-Ξ1.500000");
+        assert_eq!(
+            ETH.round_native(dec!(0.1234567890123456789), RoundingMode::ToZero),
+            dec!(0.123456789012345678)
+        );
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut r = CryptoRegistry::with_builtins();
+        assert_eq!(r.iter().count(), ALL.len());
+        assert_eq!(r.find("Usdt"), Some(USDT));
+
+        let old = r.register(CryptoCurrency::custom("usdt", "₮", 6, 4, true));
+        assert_eq!(old.as_ref(), Some(USDT));
+        assert_eq!(
+            dec_to_money_string(dec!(1.5), r.find("USDT").unwrap()),
+            "₮1.5000"
+        );
+
+        assert!(r.unregister("USDT").is_some());
+        assert_eq!(r.find("USDT"), None);
+        assert!(CryptoRegistry::new().find("BTC").is_none());
+    }
+}
@@ -0,0 +1,88 @@
+use rusty_money::{iso, FormattableCurrency};
+
+use crate::NumberSymbols;
+
+/// A currency that decimals can be formatted as
+///
+/// Implemented for the ISO 4217 currencies of `rusty_money::iso`.
+pub trait Currency {
+    /// The currency code, e.g. "USD"
+    fn code(&self) -> &str;
+
+    /// The display symbol, e.g. "$"
+    fn symbol(&self) -> &str;
+
+    /// Number of decimal places amounts are displayed with,
+    /// e.g. 2 for USD, 0 for JPY and 3 for KWD
+    fn exponent(&self) -> u32;
+
+    /// True if the symbol is placed before the amount
+    fn symbol_first(&self) -> bool;
+
+    /// The separators and digit grouping of amounts
+    fn number_symbols(&self) -> NumberSymbols;
+
+    /// True if a space separates the symbol from the amount. By default
+    /// only symbols placed after the amount or starting or ending with
+    /// a letter, such as "kr" or "Fr", are separated.
+    fn symbol_spacing(&self) -> bool {
+        let alphabetic = |c: Option<char>| c.is_some_and(char::is_alphabetic);
+        let symbol = self.symbol();
+        !self.symbol_first()
+            || alphabetic(symbol.chars().next())
+            || alphabetic(symbol.chars().last())
+    }
+}
+
+impl Currency for iso::Currency {
+    fn code(&self) -> &str {
+        FormattableCurrency::code(self)
+    }
+
+    fn symbol(&self) -> &str {
+        self.symbol
+    }
+
+    fn exponent(&self) -> u32 {
+        self.exponent
+    }
+
+    fn symbol_first(&self) -> bool {
+        self.symbol_first
+    }
+
+    fn number_symbols(&self) -> NumberSymbols {
+        match self.locale {
+            rusty_money::Locale::EnUs => NumberSymbols::new(Some(','), '.'),
+            rusty_money::Locale::EnIn => NumberSymbols::new(Some(','), '.').groups(3, 2),
+            rusty_money::Locale::EnEu => NumberSymbols::new(Some('.'), ','),
+            rusty_money::Locale::EnBy => NumberSymbols::new(Some(' '), ','),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_iso() {
+        assert_eq!(Currency::code(iso::EUR), "EUR");
+        assert_eq!(Currency::exponent(iso::JPY), 0);
+        assert_eq!(Currency::exponent(iso::KWD), 3);
+        assert_eq!(
+            iso::EUR.number_symbols(),
+            NumberSymbols::new(Some('.'), ',')
+        );
+        assert_eq!(iso::INR.number_symbols().secondary_group, 2);
+    }
+
+    #[test]
+    fn test_symbol_spacing() {
+        assert!(!iso::USD.symbol_spacing());
+        assert!(!iso::GBP.symbol_spacing());
+        assert!(iso::CHF.symbol_spacing());
+        assert!(iso::SEK.symbol_spacing());
+    }
+}
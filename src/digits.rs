@@ -0,0 +1,111 @@
+use rust_decimal::prelude::*;
+
+// A Decimal's mantissa has at most 29 digits and its scale is at most
+// 28, so 29 bytes hold every digit including a leading integral "0".
+const MAX_DIGITS: usize = 29;
+
+const CHUNK_DIGITS: usize = 19;
+const CHUNK: u128 = 10u128.pow(CHUNK_DIGITS as u32);
+
+/// The ascii digits of the absolute value of a decimal, built directly
+/// from its mantissa and scale so there is nothing to parse or unwrap
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Digits {
+    buf: [u8; MAX_DIGITS],
+    start: usize,
+    scale: usize,
+}
+
+impl Digits {
+    pub(crate) fn new(v: Decimal) -> Self {
+        let scale = (v.scale() as usize).min(MAX_DIGITS - 1);
+        let mut buf = [b'0'; MAX_DIGITS];
+        let mut m = v.mantissa().unsigned_abs();
+        let mut start = MAX_DIGITS;
+
+        // u128 division is slow, so split off the low 19 digits of a
+        // mantissa too large for a u64 and convert both parts as u64
+        while m != 0 {
+            let (rest, mut chunk, width) = if m > u64::MAX as u128 {
+                (m / CHUNK, (m % CHUNK) as u64, CHUNK_DIGITS)
+            } else {
+                (0, m as u64, MAX_DIGITS)
+            };
+            let end = start.saturating_sub(width);
+            while (chunk != 0 || rest != 0) && start > end {
+                start -= 1;
+                buf[start] = b'0' + (chunk % 10) as u8;
+                chunk /= 10;
+            }
+            m = rest;
+        }
+
+        // There is always at least one integral digit
+        start = start.min(MAX_DIGITS - scale - 1);
+
+        Self { buf, start, scale }
+    }
+
+    /// The integral digits, "0" if the value is less than 1
+    pub(crate) fn integral(&self) -> &str {
+        as_str(&self.buf[self.start..MAX_DIGITS - self.scale])
+    }
+
+    /// All `scale` fractional digits including trailing zeros
+    pub(crate) fn fractional(&self) -> &str {
+        as_str(&self.buf[MAX_DIGITS - self.scale..])
+    }
+}
+
+fn as_str(digits: &[u8]) -> &str {
+    // Only ascii digits are ever written so this never fails
+    std::str::from_utf8(digits).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn parts(v: Decimal) -> (String, String) {
+        let d = Digits::new(v);
+        (d.integral().to_owned(), d.fractional().to_owned())
+    }
+
+    #[test]
+    fn test_digits() {
+        let p = |i: &str, f: &str| (i.to_owned(), f.to_owned());
+        assert_eq!(parts(dec!(0)), p("0", ""));
+        assert_eq!(parts(dec!(0.00)), p("0", "00"));
+        assert_eq!(parts(dec!(-12.340)), p("12", "340"));
+        assert_eq!(parts(dec!(0.05)), p("0", "05"));
+        assert_eq!(parts(dec!(1000)), p("1000", ""));
+        assert_eq!(parts(Decimal::MAX), p("79228162514264337593543950335", ""));
+        assert_eq!(parts(Decimal::MIN), p("79228162514264337593543950335", ""));
+        assert_eq!(
+            parts(Decimal::new(1, 28)),
+            p("0", "0000000000000000000000000001")
+        );
+        assert_eq!(
+            parts(Decimal::from_i128_with_scale(Decimal::MAX.mantissa(), 28)),
+            p("7", "9228162514264337593543950335")
+        );
+    }
+
+    #[test]
+    fn test_digits_match_display() {
+        let values = [
+            dec!(0.1),
+            dec!(123.456),
+            dec!(-987654321.000001),
+            Decimal::new(-5, 28),
+            Decimal::from_i128_with_scale(Decimal::MIN.mantissa(), 14),
+        ];
+        for v in values {
+            let d = Digits::new(v);
+            let s = format!("{}.{}", d.integral(), d.fractional());
+            assert_eq!(s.trim_end_matches('.'), v.abs().to_string());
+        }
+    }
+}
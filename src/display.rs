@@ -0,0 +1,123 @@
+use std::fmt;
+
+use rust_decimal::prelude::*;
+
+use crate::DecimalFormatter;
+
+/// Displays a decimal like `dec_to_separated_string` without allocating
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::Separated;
+///
+/// let v = dec!(-123456.125);
+/// assert_eq!(format!("{}", Separated(&v, 2)), "-123,456.12");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Separated<'a>(pub &'a Decimal, pub u32);
+
+impl Separated<'_> {
+    /// Write the value to `w`
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+        DecimalFormatter::separated(self.1).write_to(*self.0, w)
+    }
+}
+
+impl fmt::Display for Separated<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
+
+/// Displays a decimal like `dec_to_usd_string` without allocating
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::Usd;
+///
+/// let v = dec!(1234.565);
+/// assert_eq!(format!("{}", Usd(&v)), "$1,234.56");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Usd<'a>(pub &'a Decimal);
+
+impl Usd<'_> {
+    /// Write the value to `w`
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+        DecimalFormatter::usd().write_to(*self.0, w)
+    }
+}
+
+impl fmt::Display for Usd<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{dec_to_separated_string, dec_to_usd_string};
+    use rust_decimal_macros::dec;
+
+    // A fixed size buffer, so writing to it can't allocate
+    struct Buf {
+        bytes: [u8; 64],
+        len: usize,
+    }
+
+    impl fmt::Write for Buf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_matches_functions() {
+        let values = [
+            dec!(0),
+            dec!(-0.001),
+            dec!(1234567.891),
+            Decimal::MAX,
+            Decimal::MIN,
+            Decimal::new(1, 28),
+        ];
+        for v in values {
+            assert_eq!(Usd(&v).to_string(), dec_to_usd_string(v));
+            for dp in [0, 2, 28] {
+                assert_eq!(
+                    Separated(&v, dp).to_string(),
+                    dec_to_separated_string(v, dp)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_to() {
+        let mut buf = Buf {
+            bytes: [0; 64],
+            len: 0,
+        };
+        let v = dec!(-9876.5);
+        Usd(&v).write_to(&mut buf).unwrap();
+        Separated(&v, 0).write_to(&mut buf).unwrap();
+        assert_eq!(&buf.bytes[..buf.len], "-$9,876.50-9,876".as_bytes());
+
+        // Errors from the writer are returned
+        let v = Decimal::MAX;
+        assert!(Separated(&v, 0).write_to(&mut buf).is_ok());
+        assert!(Separated(&v, 0).write_to(&mut buf).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use rust_decimal::Decimal;
+
+use crate::FilterError;
+
+/// Errors returned by the `try_` functions of this crate
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecUtilsError {
+    /// The group separator and decimal mark are equal or one of
+    /// them is a digit, so formatted values would be ambiguous
+    InvalidSymbols {
+        group_separator: Option<char>,
+        decimal_mark: char,
+    },
+
+    /// The fill could be mistaken for a sign, digit or separator of a
+    /// formatted value
+    InvalidFill(char),
+
+    /// No currency is known by this code
+    UnknownCurrency(String),
+
+    /// The string is not a number in the expected format or the
+    /// number can not be represented exactly by a `Decimal`
+    InvalidNumber(String),
+
+    /// The result of an arithmetic operation is too large for a `Decimal`
+    Overflow,
+
+    /// The number is too large for a `Decimal`
+    OutOfRange(String),
+
+    /// The number has more decimal places than a `Decimal` can hold
+    /// with its integral digits, so it can't be represented exactly
+    ExcessiveScale(String),
+
+    /// The amounts of an operation are in different currencies
+    CurrencyMismatch { left: String, right: String },
+
+    /// A value was divided by zero
+    DivisionByZero,
+
+    /// An amount can't be allocated, e.g. because there are no ratios
+    InvalidAllocation(String),
+
+    /// There is no exchange rate to convert between the currencies
+    MissingRate { from: String, to: String },
+
+    /// An exchange rate isn't positive
+    InvalidRate(Decimal),
+
+    /// A ledger quantity isn't positive
+    InvalidQuantity(Decimal),
+
+    /// A ledger price is negative
+    InvalidPrice(Decimal),
+
+    /// A ledger fee is negative
+    InvalidFee(Decimal),
+
+    /// An order violates a symbol filter
+    Filter(FilterError),
+
+    /// More was sold than the lots of a ledger hold
+    InsufficientLots { held: Decimal, qty: Decimal },
+}
+
+impl fmt::Display for DecUtilsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecUtilsError::InvalidSymbols {
+                group_separator,
+                decimal_mark,
+            } => {
+                f.write_str("invalid number symbols, group separator ")?;
+                match group_separator {
+                    Some(c) => write!(f, "{:?}", c)?,
+                    None => f.write_str("none")?,
+                }
+                write!(f, " decimal mark {:?}", decimal_mark)
+            }
+            DecUtilsError::InvalidFill(fill) => write!(f, "invalid fill {:?}", fill),
+            DecUtilsError::UnknownCurrency(code) => write!(f, "unknown currency {:?}", code),
+            DecUtilsError::InvalidNumber(s) => write!(f, "invalid number {:?}", s),
+            DecUtilsError::Overflow => write!(f, "decimal overflow"),
+            DecUtilsError::OutOfRange(s) => write!(f, "number {:?} is too large for a decimal", s),
+            DecUtilsError::ExcessiveScale(s) => {
+                write!(
+                    f,
+                    "number {:?} has too many decimal places for a decimal",
+                    s
+                )
+            }
+            DecUtilsError::CurrencyMismatch { left, right } => {
+                write!(f, "currency mismatch, {} and {}", left, right)
+            }
+            DecUtilsError::DivisionByZero => write!(f, "division by zero"),
+            DecUtilsError::InvalidAllocation(reason) => write!(f, "invalid allocation, {}", reason),
+            DecUtilsError::MissingRate { from, to } => {
+                write!(f, "no exchange rate from {} to {}", from, to)
+            }
+            DecUtilsError::InvalidRate(rate) => write!(f, "invalid exchange rate {}", rate),
+            DecUtilsError::InvalidQuantity(qty) => write!(f, "invalid quantity {}", qty),
+            DecUtilsError::InvalidPrice(price) => write!(f, "invalid price {}", price),
+            DecUtilsError::InvalidFee(fee) => write!(f, "invalid fee {}", fee),
+            DecUtilsError::Filter(e) => e.fmt(f),
+            DecUtilsError::InsufficientLots { held, qty } => {
+                write!(f, "can't dispose of {}, only {} held", qty, held)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecUtilsError {}
+
+impl From<FilterError> for DecUtilsError {
+    fn from(e: FilterError) -> Self {
+        DecUtilsError::Filter(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let e = DecUtilsError::InvalidSymbols {
+            group_separator: Some(','),
+            decimal_mark: ',',
+        };
+        assert_eq!(
+            e.to_string(),
+            "invalid number symbols, group separator ',' decimal mark ','"
+        );
+        let e = DecUtilsError::InvalidSymbols {
+            group_separator: None,
+            decimal_mark: '1',
+        };
+        assert_eq!(
+            e.to_string(),
+            "invalid number symbols, group separator none decimal mark '1'"
+        );
+        let e = DecUtilsError::InvalidFill('-');
+        assert_eq!(e.to_string(), "invalid fill '-'");
+        let e = DecUtilsError::UnknownCurrency("XYZ".to_owned());
+        assert_eq!(e.to_string(), "unknown currency \"XYZ\"");
+        let e = DecUtilsError::InvalidNumber("1,2.3.4".to_owned());
+        assert_eq!(e.to_string(), "invalid number \"1,2.3.4\"");
+        assert_eq!(DecUtilsError::Overflow.to_string(), "decimal overflow");
+        let e = DecUtilsError::OutOfRange("1e29".to_owned());
+        assert_eq!(e.to_string(), "number \"1e29\" is too large for a decimal");
+        let e = DecUtilsError::ExcessiveScale("1e-29".to_owned());
+        assert_eq!(
+            e.to_string(),
+            "number \"1e-29\" has too many decimal places for a decimal"
+        );
+        let e = DecUtilsError::CurrencyMismatch {
+            left: "USD".to_owned(),
+            right: "BTC".to_owned(),
+        };
+        assert_eq!(e.to_string(), "currency mismatch, USD and BTC");
+        assert_eq!(
+            DecUtilsError::DivisionByZero.to_string(),
+            "division by zero"
+        );
+        let e = DecUtilsError::InvalidAllocation("no ratios".to_owned());
+        assert_eq!(e.to_string(), "invalid allocation, no ratios");
+        let e = DecUtilsError::MissingRate {
+            from: "EUR".to_owned(),
+            to: "JPY".to_owned(),
+        };
+        assert_eq!(e.to_string(), "no exchange rate from EUR to JPY");
+        let e = DecUtilsError::InvalidRate(Decimal::ZERO);
+        assert_eq!(e.to_string(), "invalid exchange rate 0");
+        let e = DecUtilsError::InvalidQuantity(Decimal::ZERO);
+        assert_eq!(e.to_string(), "invalid quantity 0");
+        let e = DecUtilsError::InvalidPrice(-Decimal::ONE);
+        assert_eq!(e.to_string(), "invalid price -1");
+        let e = DecUtilsError::InvalidFee(-Decimal::ONE);
+        assert_eq!(e.to_string(), "invalid fee -1");
+        let e = DecUtilsError::from(FilterError::PriceBelowMin {
+            price: Decimal::new(5, 1),
+            min: Decimal::ONE,
+        });
+        assert_eq!(e.to_string(), "price 0.5 is below the minimum 1");
+        let e = DecUtilsError::InsufficientLots {
+            held: Decimal::ONE,
+            qty: Decimal::TWO,
+        };
+        assert_eq!(e.to_string(), "can't dispose of 2, only 1 held");
+    }
+}
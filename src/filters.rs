@@ -0,0 +1,460 @@
+use std::fmt;
+
+use rust_decimal::prelude::*;
+
+use crate::{DecUtilsError, RoundingMode, ScientificFormatter};
+
+/// An order that violates a `SymbolFilters` rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FilterError {
+    /// The price isn't a multiple of the tick size
+    PriceOffTick { price: Decimal, tick_size: Decimal },
+
+    /// The price is below the minimum price
+    PriceBelowMin { price: Decimal, min: Decimal },
+
+    /// The price is above the maximum price
+    PriceAboveMax { price: Decimal, max: Decimal },
+
+    /// The quantity isn't a multiple of the step size
+    QtyOffStep { qty: Decimal, step_size: Decimal },
+
+    /// The quantity is below the minimum quantity
+    QtyBelowMin { qty: Decimal, min: Decimal },
+
+    /// The quantity is above the maximum quantity
+    QtyAboveMax { qty: Decimal, max: Decimal },
+
+    /// Price times quantity is below the minimum notional
+    NotionalBelowMin { notional: Decimal, min: Decimal },
+
+    /// The price is zero or negative
+    PriceNotPositive { price: Decimal },
+
+    /// The quantity is zero or negative
+    QtyNotPositive { qty: Decimal },
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterError::PriceOffTick { price, tick_size } => {
+                write!(
+                    f,
+                    "price {} is not a multiple of tick size {}",
+                    price, tick_size
+                )
+            }
+            FilterError::PriceBelowMin { price, min } => {
+                write!(f, "price {} is below the minimum {}", price, min)
+            }
+            FilterError::PriceAboveMax { price, max } => {
+                write!(f, "price {} is above the maximum {}", price, max)
+            }
+            FilterError::QtyOffStep { qty, step_size } => {
+                write!(
+                    f,
+                    "quantity {} is not a multiple of step size {}",
+                    qty, step_size
+                )
+            }
+            FilterError::QtyBelowMin { qty, min } => {
+                write!(f, "quantity {} is below the minimum {}", qty, min)
+            }
+            FilterError::QtyAboveMax { qty, max } => {
+                write!(f, "quantity {} is above the maximum {}", qty, max)
+            }
+            FilterError::NotionalBelowMin { notional, min } => {
+                write!(f, "notional {} is below the minimum {}", notional, min)
+            }
+            FilterError::PriceNotPositive { price } => {
+                write!(f, "price {} is not positive", price)
+            }
+            FilterError::QtyNotPositive { qty } => write!(f, "quantity {} is not positive", qty),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Number of decimal places of a tick or step size, ignoring trailing
+/// zeros, e.g. 3 for "0.00100000"
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::step_precision;
+///
+/// assert_eq!(step_precision(dec!(0.00100000)), 3);
+/// assert_eq!(step_precision(dec!(0.5)), 1);
+/// assert_eq!(step_precision(dec!(10)), 0);
+/// ```
+pub fn step_precision(step: Decimal) -> u32 {
+    step.normalize().scale()
+}
+
+/// The price and quantity rules of an exchange symbol
+///
+/// A tick or step size of zero, the way exchanges report a disabled
+/// filter, disables rounding and the multiple check. Rounded values have
+/// exactly the precision of the tick or step size.
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{FilterError, RoundingMode, SymbolFilters};
+///
+/// let filters = SymbolFilters::from_strs("0.01000000", "0.00001000")
+///     .unwrap()
+///     .min_qty(dec!(0.00001))
+///     .min_notional(dec!(5));
+/// assert_eq!(filters.price_precision(), 2);
+/// assert_eq!(filters.qty_precision(), 5);
+///
+/// let price = filters.round_price(dec!(64250.137), RoundingMode::default()).unwrap();
+/// let qty = filters.round_qty(dec!(0.000123456), RoundingMode::ToZero).unwrap();
+/// assert_eq!(price.to_string(), "64250.14");
+/// assert_eq!(qty.to_string(), "0.00012");
+/// assert_eq!(filters.validate(price, qty), Ok(()));
+///
+/// assert_eq!(
+///     filters.validate(price, dec!(0.00007)),
+///     Err(FilterError::NotionalBelowMin {
+///         notional: dec!(4.4975098),
+///         min: dec!(5)
+///     })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SymbolFilters {
+    tick_size: Decimal,
+    step_size: Decimal,
+    min_price: Option<Decimal>,
+    max_price: Option<Decimal>,
+    min_qty: Option<Decimal>,
+    max_qty: Option<Decimal>,
+    min_notional: Option<Decimal>,
+}
+
+impl SymbolFilters {
+    /// Filters rounding prices to `tick_size` and quantities to
+    /// `step_size`, with no limits
+    pub fn new(tick_size: Decimal, step_size: Decimal) -> Self {
+        Self {
+            tick_size: tick_size.abs(),
+            step_size: step_size.abs(),
+            ..Self::default()
+        }
+    }
+
+    /// Filters from tick and step size strings as exchanges send them,
+    /// e.g. "0.01000000"
+    ///
+    /// Returns `DecUtilsError::InvalidNumber` if a size isn't a number.
+    pub fn from_strs(tick_size: &str, step_size: &str) -> Result<Self, DecUtilsError> {
+        Ok(Self::new(
+            ScientificFormatter::parse(tick_size)?,
+            ScientificFormatter::parse(step_size)?,
+        ))
+    }
+
+    /// The minimum price
+    pub fn min_price(mut self, min_price: Decimal) -> Self {
+        self.min_price = Some(min_price);
+        self
+    }
+
+    /// The maximum price
+    pub fn max_price(mut self, max_price: Decimal) -> Self {
+        self.max_price = Some(max_price);
+        self
+    }
+
+    /// The minimum quantity
+    pub fn min_qty(mut self, min_qty: Decimal) -> Self {
+        self.min_qty = Some(min_qty);
+        self
+    }
+
+    /// The maximum quantity
+    pub fn max_qty(mut self, max_qty: Decimal) -> Self {
+        self.max_qty = Some(max_qty);
+        self
+    }
+
+    /// The minimum of price times quantity
+    pub fn min_notional(mut self, min_notional: Decimal) -> Self {
+        self.min_notional = Some(min_notional);
+        self
+    }
+
+    /// The price increment
+    pub fn tick_size(&self) -> Decimal {
+        self.tick_size
+    }
+
+    /// The quantity increment
+    pub fn step_size(&self) -> Decimal {
+        self.step_size
+    }
+
+    /// Number of decimal places of prices, see `step_precision`
+    pub fn price_precision(&self) -> u32 {
+        step_precision(self.tick_size)
+    }
+
+    /// Number of decimal places of quantities, see `step_precision`
+    pub fn qty_precision(&self) -> u32 {
+        step_precision(self.step_size)
+    }
+
+    /// Round `price` to a multiple of the tick size, e.g. with
+    /// `RoundingMode::FLOOR` for a buy or `RoundingMode::CEILING` for a
+    /// sell
+    ///
+    /// Returns `DecUtilsError::Overflow` if the price is too large.
+    pub fn round_price(
+        &self,
+        price: Decimal,
+        rounding: RoundingMode,
+    ) -> Result<Decimal, DecUtilsError> {
+        round_to_step(price, self.tick_size, rounding)
+    }
+
+    /// Round `qty` to a multiple of the step size, usually with
+    /// `RoundingMode::ToZero` so the order never exceeds the balance
+    ///
+    /// Returns `DecUtilsError::Overflow` if the quantity is too large.
+    pub fn round_qty(
+        &self,
+        qty: Decimal,
+        rounding: RoundingMode,
+    ) -> Result<Decimal, DecUtilsError> {
+        round_to_step(qty, self.step_size, rounding)
+    }
+
+    /// Check an order's price and quantity against every filter,
+    /// returning the first violation, a price or quantity that isn't
+    /// positive is always a violation
+    pub fn validate(&self, price: Decimal, qty: Decimal) -> Result<(), FilterError> {
+        if price.is_sign_negative() || price.is_zero() {
+            return Err(FilterError::PriceNotPositive { price });
+        }
+        if qty.is_sign_negative() || qty.is_zero() {
+            return Err(FilterError::QtyNotPositive { qty });
+        }
+        if !is_multiple(price, self.tick_size) {
+            return Err(FilterError::PriceOffTick {
+                price,
+                tick_size: self.tick_size,
+            });
+        }
+        if let Some(min) = self.min_price.filter(|&min| price < min) {
+            return Err(FilterError::PriceBelowMin { price, min });
+        }
+        if let Some(max) = self.max_price.filter(|&max| price > max) {
+            return Err(FilterError::PriceAboveMax { price, max });
+        }
+        if !is_multiple(qty, self.step_size) {
+            return Err(FilterError::QtyOffStep {
+                qty,
+                step_size: self.step_size,
+            });
+        }
+        if let Some(min) = self.min_qty.filter(|&min| qty < min) {
+            return Err(FilterError::QtyBelowMin { qty, min });
+        }
+        if let Some(max) = self.max_qty.filter(|&max| qty > max) {
+            return Err(FilterError::QtyAboveMax { qty, max });
+        }
+        if let Some(min) = self.min_notional {
+            // A notional too large for a Decimal is above any minimum
+            if let Some(notional) = price.checked_mul(qty).filter(|&n| n < min) {
+                return Err(FilterError::NotionalBelowMin { notional, min });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn round_to_step(
+    v: Decimal,
+    step: Decimal,
+    rounding: RoundingMode,
+) -> Result<Decimal, DecUtilsError> {
+    if step.is_zero() {
+        return Ok(v);
+    }
+    let steps = v.checked_div(step).ok_or(DecUtilsError::Overflow)?;
+    let mut rounded = rounding
+        .round_dp(steps, 0)
+        .checked_mul(step)
+        .ok_or(DecUtilsError::Overflow)?;
+    // A value too large for the precision keeps its old scale
+    let precision = step_precision(step);
+    rounded.rescale(precision);
+    if rounded.scale() != precision {
+        return Err(DecUtilsError::Overflow);
+    }
+    Ok(rounded)
+}
+
+fn is_multiple(v: Decimal, step: Decimal) -> bool {
+    step.is_zero() || v.checked_rem(step).is_some_and(|rem| rem.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_round() {
+        let f = SymbolFilters::new(dec!(0.05), dec!(0.00100000));
+        let price = |v, rounding| f.round_price(v, rounding).unwrap().to_string();
+        assert_eq!(price(dec!(1.024), RoundingMode::default()), "1.00");
+        assert_eq!(price(dec!(1.025), RoundingMode::default()), "1.00");
+        assert_eq!(price(dec!(1.075), RoundingMode::default()), "1.10");
+        assert_eq!(price(dec!(1.026), RoundingMode::FLOOR), "1.00");
+        assert_eq!(price(dec!(1.001), RoundingMode::CEILING), "1.05");
+        assert_eq!(price(dec!(7), RoundingMode::CEILING), "7.00");
+
+        let qty = |v, rounding| f.round_qty(v, rounding).unwrap().to_string();
+        assert_eq!(qty(dec!(1.23456), RoundingMode::ToZero), "1.234");
+        assert_eq!(qty(dec!(1.23456), RoundingMode::AwayFromZero), "1.235");
+        assert_eq!(qty(dec!(1.2345), RoundingMode::HALF_UP), "1.235");
+        assert_eq!(qty(dec!(-1.23456), RoundingMode::ToZero), "-1.234");
+        assert_eq!(qty(dec!(0.0009), RoundingMode::ToZero), "0.000");
+
+        let f = SymbolFilters::new(dec!(0), dec!(10));
+        assert_eq!(
+            f.round_price(dec!(1.23456), RoundingMode::ToZero),
+            Ok(dec!(1.23456))
+        );
+        assert_eq!(
+            f.round_qty(dec!(1234), RoundingMode::ToZero)
+                .unwrap()
+                .to_string(),
+            "1230"
+        );
+
+        let f = SymbolFilters::new(dec!(0.0000000001), dec!(1));
+        assert_eq!(
+            f.round_price(Decimal::MAX, RoundingMode::ToZero),
+            Err(DecUtilsError::Overflow)
+        );
+        // 10^28 is a multiple of 0.5 but can't have a decimal place
+        let f = SymbolFilters::new(dec!(0.5), dec!(1));
+        assert_eq!(
+            f.round_price(dec!(1e28), RoundingMode::ToZero),
+            Err(DecUtilsError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_from_strs() {
+        let f = SymbolFilters::from_strs("0.00010000", "1.00000000").unwrap();
+        assert_eq!(f.tick_size(), dec!(0.0001));
+        assert_eq!(f.step_size(), dec!(1));
+        assert_eq!(f.price_precision(), 4);
+        assert_eq!(f.qty_precision(), 0);
+        assert_eq!(
+            SymbolFilters::from_strs("0.01", "abc"),
+            Err(DecUtilsError::InvalidNumber("abc".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let f = SymbolFilters::new(dec!(0.01), dec!(0.001))
+            .min_price(dec!(0.10))
+            .max_price(dec!(1000))
+            .min_qty(dec!(0.01))
+            .max_qty(dec!(100))
+            .min_notional(dec!(10));
+        assert_eq!(f.validate(dec!(50.00), dec!(0.200)), Ok(()));
+        assert_eq!(
+            f.validate(dec!(50.005), dec!(1)),
+            Err(FilterError::PriceOffTick {
+                price: dec!(50.005),
+                tick_size: dec!(0.01)
+            })
+        );
+        assert_eq!(
+            f.validate(dec!(0.05), dec!(1)),
+            Err(FilterError::PriceBelowMin {
+                price: dec!(0.05),
+                min: dec!(0.10)
+            })
+        );
+        assert_eq!(
+            f.validate(dec!(1000.01), dec!(1)),
+            Err(FilterError::PriceAboveMax {
+                price: dec!(1000.01),
+                max: dec!(1000)
+            })
+        );
+        assert_eq!(
+            f.validate(dec!(50), dec!(0.2005)),
+            Err(FilterError::QtyOffStep {
+                qty: dec!(0.2005),
+                step_size: dec!(0.001)
+            })
+        );
+        assert_eq!(
+            f.validate(dec!(999), dec!(0.009)),
+            Err(FilterError::QtyBelowMin {
+                qty: dec!(0.009),
+                min: dec!(0.01)
+            })
+        );
+        assert_eq!(
+            f.validate(dec!(1), dec!(100.001)),
+            Err(FilterError::QtyAboveMax {
+                qty: dec!(100.001),
+                max: dec!(100)
+            })
+        );
+        assert_eq!(
+            f.validate(dec!(9.99), dec!(1)),
+            Err(FilterError::NotionalBelowMin {
+                notional: dec!(9.99),
+                min: dec!(10)
+            })
+        );
+        assert_eq!(
+            SymbolFilters::default().validate(dec!(1.23456789), dec!(0.1)),
+            Ok(())
+        );
+        assert_eq!(
+            SymbolFilters::default().validate(dec!(-1), dec!(-1)),
+            Err(FilterError::PriceNotPositive { price: dec!(-1) })
+        );
+        assert_eq!(
+            SymbolFilters::default().validate(dec!(1), dec!(0)),
+            Err(FilterError::QtyNotPositive { qty: dec!(0) })
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let e = FilterError::QtyOffStep {
+            qty: dec!(0.2005),
+            step_size: dec!(0.001),
+        };
+        assert_eq!(
+            e.to_string(),
+            "quantity 0.2005 is not a multiple of step size 0.001"
+        );
+        let e: DecUtilsError = FilterError::NotionalBelowMin {
+            notional: dec!(9.99),
+            min: dec!(10),
+        }
+        .into();
+        assert_eq!(e.to_string(), "notional 9.99 is below the minimum 10");
+    }
+}
@@ -0,0 +1,1212 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use rust_decimal::prelude::*;
+
+use rusty_money::iso;
+
+use crate::digits::Digits;
+use crate::rounding::msd_exponent;
+use crate::{Currency, DecUtilsError, Locale, NumberSymbols, RoundingMode};
+
+/// How the sign of a value is rendered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SignStyle {
+    /// A leading "-" on negative values, nothing on positive values
+    #[default]
+    Negative,
+
+    /// A leading "-" on negative values and a leading "+" on all others
+    Always,
+
+    /// Negative values in parentheses, e.g. "($1,234.56)"
+    Parentheses,
+
+    /// A trailing "-" on negative values, e.g. "1,234.56-"
+    TrailingMinus,
+
+    /// A trailing " CR" on negative values, e.g. "$1,234.56 CR"
+    Cr,
+
+    /// A trailing " CR" on negative values and " DR" on all others
+    CrDr,
+}
+
+/// How values that are zero after rounding are rendered
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{DecimalFormatter, ZeroStyle};
+///
+/// let f = DecimalFormatter::separated(2);
+/// assert_eq!(f.format(dec!(-0.001)), "0.00");
+///
+/// let f = f.zero_style(ZeroStyle::Signed);
+/// assert_eq!(f.format(dec!(-0.001)), "-0.00");
+///
+/// let f = f.zero_style(ZeroStyle::Text("—".to_owned()));
+/// assert_eq!(f.format(dec!(-0.001)), "—");
+/// assert_eq!(f.format(dec!(0)), "—");
+/// assert_eq!(f.format(dec!(0.01)), "0.01");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ZeroStyle {
+    /// Never render a "-" on zero, e.g. -0.001 to 2 places is "0.00"
+    #[default]
+    Unsigned,
+
+    /// Keep the sign of the unrounded value, e.g. "-0.00"
+    Signed,
+
+    /// Render this text, without prefix or suffix, in place of zero
+    Text(String),
+}
+
+/// Where a value is placed when it is shorter than the width
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// Fill after the value
+    Left,
+
+    /// Fill before the value
+    #[default]
+    Right,
+
+    /// Fill before and after the value so the decimal marks of values
+    /// from this formatter line up
+    ///
+    /// Room is kept after the digits for the most fractional digits
+    /// the formatter can produce, when it has decimal places, and for
+    /// the longest trailing sign of the sign style.
+    DecimalMark,
+}
+
+/// Where the fill before a value goes
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{DecimalFormatter, PadPosition};
+///
+/// let f = DecimalFormatter::usd().width(10);
+/// assert_eq!(f.format(dec!(-12.3)), "   -$12.30");
+///
+/// let f = f.pad_position(PadPosition::AfterSign);
+/// assert_eq!(f.format(dec!(-12.3)), "-$   12.30");
+///
+/// let f = f.pad_position(PadPosition::AfterPrefix);
+/// assert_eq!(f.format(dec!(-12.3)), "$   -12.30");
+/// assert_eq!(f.format(dec!(1234.5)), "$ 1,234.50");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PadPosition {
+    /// Before the sign and prefix, e.g. "   -$12.30"
+    #[default]
+    BeforeSign,
+
+    /// After the sign and prefix, e.g. "-$   12.30" or with a "0"
+    /// fill "-$00012.30"
+    AfterSign,
+
+    /// After the prefix with a leading sign moved next to the digits,
+    /// e.g. "$   -12.30", so prefixes line up in a column
+    AfterPrefix,
+}
+
+/// A reusable, configurable decimal formatter
+///
+/// The formatter is built once with the desired decimal places,
+/// rounding mode, separators, sign style, prefix/suffix and
+/// empty-value text and may then be used to format any number of values.
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{DecimalFormatter, RoundingMode};
+///
+/// let f = DecimalFormatter::new()
+///     .dp(2)
+///     .min_dp(2)
+///     .rounding(RoundingMode::HALF_UP)
+///     .group_separator(Some('.'))
+///     .decimal_mark(',')
+///     .suffix(" €")
+///     .empty("n/a");
+///
+/// assert_eq!(f.format(dec!(1234567.125)), "1.234.567,13 €");
+/// assert_eq!(f.format(dec!(-5)), "-5,00 €");
+/// assert_eq!(f.format_option(None), "n/a");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecimalFormatter {
+    dp: Option<u32>,
+    min_dp: u32,
+    significant_digits: Option<u32>,
+    trim_trailing_zeros: bool,
+    rounding: RoundingMode,
+    symbols: NumberSymbols,
+    sign_style: SignStyle,
+    zero_style: ZeroStyle,
+    prefix: Cow<'static, str>,
+    suffix: Cow<'static, str>,
+    empty: Cow<'static, str>,
+    width: usize,
+    fill: char,
+    alignment: Alignment,
+    pad_position: PadPosition,
+}
+
+impl Default for DecimalFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecimalFormatter {
+    /// A formatter that renders values like `Decimal`'s `Display`,
+    /// no rounding, no grouping, a "." decimal mark and "" as the
+    /// empty-value text.
+    pub fn new() -> Self {
+        Self {
+            dp: None,
+            min_dp: 0,
+            significant_digits: None,
+            trim_trailing_zeros: false,
+            rounding: RoundingMode::MidpointNearestEven,
+            symbols: NumberSymbols::default(),
+            sign_style: SignStyle::Negative,
+            zero_style: ZeroStyle::Unsigned,
+            prefix: Cow::Borrowed(""),
+            suffix: Cow::Borrowed(""),
+            empty: Cow::Borrowed(""),
+            width: 0,
+            fill: ' ',
+            alignment: Alignment::Right,
+            pad_position: PadPosition::BeforeSign,
+        }
+    }
+
+    /// The preset used by `dec_to_separated_string`, "Bankers Rounding"
+    /// to `dp` decimal places with a "," at every 1,000 place.
+    pub fn separated(dp: u32) -> Self {
+        Self::new().dp(dp).group_separator(Some(','))
+    }
+
+    /// The preset used by `dec_to_usd_string`, a "$" prefix, "Bankers
+    /// Rounding" to exactly 2 decimal places and a "," at every 1,000 place.
+    pub fn usd() -> Self {
+        // Equal to money(iso::USD) but the "$" is borrowed, so building
+        // this preset never allocates
+        Self {
+            prefix: Cow::Borrowed("$"),
+            ..Self::new()
+                .fixed_dp(iso::USD.exponent())
+                .symbols(iso::USD.number_symbols())
+        }
+    }
+
+    /// A preset for `currency`, "Bankers Rounding" to exactly the
+    /// currency's exponent decimal places using its symbol, symbol
+    /// placement and spacing, separators and digit grouping.
+    pub fn money<C: Currency + ?Sized>(currency: &C) -> Self {
+        let f = Self::new()
+            .fixed_dp(currency.exponent())
+            .symbols(currency.number_symbols());
+        let space = if currency.symbol_spacing() { " " } else { "" };
+        if currency.symbol_first() {
+            f.prefix(&format!("{}{}", currency.symbol(), space))
+        } else {
+            f.suffix(&format!("{}{}", space, currency.symbol()))
+        }
+    }
+
+    /// Round to at most `dp` decimal places
+    pub fn dp(mut self, dp: u32) -> Self {
+        self.dp = Some(dp);
+        self.significant_digits = None;
+        self
+    }
+
+    /// Round to `significant_digits` significant digits, zero padding
+    /// the fractional part so all of them are shown. This replaces the
+    /// decimal places until `dp` is set again.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::DecimalFormatter;
+    ///
+    /// let f = DecimalFormatter::separated(0).significant_digits(4);
+    /// assert_eq!(f.format(dec!(0.00000123)), "0.000001230");
+    /// assert_eq!(f.format(dec!(65000)), "65,000");
+    /// assert_eq!(f.format(dec!(65432.1)), "65,430");
+    /// assert_eq!(f.format(dec!(1.5)), "1.500");
+    /// ```
+    pub fn significant_digits(mut self, significant_digits: u32) -> Self {
+        self.significant_digits = Some(significant_digits);
+        self
+    }
+
+    /// Zero pad the fractional part to at least `min_dp` digits
+    pub fn min_dp(mut self, min_dp: u32) -> Self {
+        self.min_dp = min_dp;
+        self
+    }
+
+    /// Always emit exactly `dp` fractional digits, e.g. "5.00" for 5
+    pub fn fixed_dp(self, dp: u32) -> Self {
+        self.dp(dp).min_dp(dp)
+    }
+
+    /// Remove trailing fractional zeros, but never below `min_dp` digits
+    pub fn trim_trailing_zeros(mut self, trim: bool) -> Self {
+        self.trim_trailing_zeros = trim;
+        self
+    }
+
+    /// ICU style minimum and maximum fraction digits, round to `max`
+    /// places then trim trailing zeros down to `min` places
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::DecimalFormatter;
+    ///
+    /// let f = DecimalFormatter::new().fraction_digits(2, 4);
+    /// assert_eq!(f.format(dec!(1)), "1.00");
+    /// assert_eq!(f.format(dec!(1.1000)), "1.10");
+    /// assert_eq!(f.format(dec!(1.123)), "1.123");
+    /// assert_eq!(f.format(dec!(1.123456)), "1.1235");
+    /// ```
+    pub fn fraction_digits(self, min: u32, max: u32) -> Self {
+        self.dp(max).min_dp(min).trim_trailing_zeros(true)
+    }
+
+    /// The mode used when rounding to `dp` decimal places,
+    /// the default is "Bankers Rounding"
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// The separator inserted between digit groups, None for no grouping
+    pub fn group_separator(mut self, separator: Option<char>) -> Self {
+        self.symbols.group_separator = separator;
+        self
+    }
+
+    /// The character between the integral and fractional parts
+    pub fn decimal_mark(mut self, mark: char) -> Self {
+        self.symbols.decimal_mark = mark;
+        self
+    }
+
+    /// Use the separators and digit grouping of `symbols`
+    pub fn symbols(mut self, symbols: NumberSymbols) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Use the separators and digit grouping of `locale`
+    pub fn locale(self, locale: Locale) -> Self {
+        self.symbols(locale.symbols())
+    }
+
+    /// How the sign is rendered
+    pub fn sign_style(mut self, sign_style: SignStyle) -> Self {
+        self.sign_style = sign_style;
+        self
+    }
+
+    /// How values that are zero after rounding are rendered
+    pub fn zero_style(mut self, zero_style: ZeroStyle) -> Self {
+        self.zero_style = zero_style;
+        self
+    }
+
+    /// Text placed after the sign and before the digits, such as "$"
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Cow::Owned(prefix.to_owned());
+        self
+    }
+
+    /// Text placed after the digits, such as " USD"
+    pub fn suffix(mut self, suffix: &str) -> Self {
+        self.suffix = Cow::Owned(suffix.to_owned());
+        self
+    }
+
+    /// Insert `unit`, such as the "K" of "1.2K", between the digits
+    /// and the suffix
+    pub(crate) fn unit(mut self, unit: &str) -> Self {
+        self.suffix.to_mut().insert_str(0, unit);
+        self
+    }
+
+    /// Text returned by `format_option` for None
+    pub fn empty(mut self, empty: &str) -> Self {
+        self.empty = Cow::Owned(empty.to_owned());
+        self
+    }
+
+    /// Pad values to at least `width` characters, 0 for no padding
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::{Alignment, DecimalFormatter, SignStyle};
+    ///
+    /// let f = DecimalFormatter::separated(2).width(10);
+    /// assert_eq!(f.format(dec!(-1234.5)), "  -1,234.5");
+    /// assert_eq!(f.clone().fill('*').format(dec!(1.5)), "*******1.5");
+    /// assert_eq!(f.clone().align(Alignment::Left).format(dec!(1.5)), "1.5       ");
+    ///
+    /// let f = DecimalFormatter::new()
+    ///     .fraction_digits(0, 2)
+    ///     .sign_style(SignStyle::Parentheses)
+    ///     .width(10)
+    ///     .align(Alignment::DecimalMark);
+    /// assert_eq!(f.format(dec!(12.25)), "    12.25 ");
+    /// assert_eq!(f.format(dec!(-1.5)), "    (1.5) ");
+    /// assert_eq!(f.format(dec!(100)), "   100    ");
+    /// ```
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// The character used to pad values to the width, " " by default
+    ///
+    /// A "0" fill is zero padding, it always goes after the sign and
+    /// only right aligned values may use it. Any other fill that could
+    /// be mistaken for a sign, including the letters of "CR" and "DR",
+    /// a digit or a separator makes the formatter invalid.
+    pub fn fill(mut self, fill: char) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Where values shorter than the width are placed, right by default
+    pub fn align(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Where the fill before a value goes, see `PadPosition`
+    pub fn pad_position(mut self, pad_position: PadPosition) -> Self {
+        self.pad_position = pad_position;
+        self
+    }
+
+    /// Format `v`
+    ///
+    /// If the formatter is invalid, see `try_format`, `v` is formatted
+    /// using `Decimal`'s `Display` instead. The presets are always valid.
+    pub fn format(&self, v: Decimal) -> String {
+        self.try_format(v).unwrap_or_else(|_| v.to_string())
+    }
+
+    /// Format `v` or return the empty-value text if None
+    ///
+    /// The fallback for an invalid formatter is the same as `format`.
+    pub fn format_option(&self, v: Option<Decimal>) -> String {
+        match v {
+            Some(v) => self.format(v),
+            None => self.padded_empty(),
+        }
+    }
+
+    /// Format `v` or return an error if the group separator, decimal
+    /// mark or fill would make the result ambiguous
+    pub fn try_format(&self, v: Decimal) -> Result<String, DecUtilsError> {
+        self.validate()?;
+
+        let mut s = String::with_capacity(32 + self.prefix.len() + self.suffix.len());
+        // Writing to a String never fails
+        let _ = self.write_valid(v, &mut s);
+
+        Ok(s)
+    }
+
+    /// Write `v` to `w` without allocating
+    ///
+    /// The fallback for an invalid formatter is the same as `format`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fmt::Write;
+    ///
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::DecimalFormatter;
+    ///
+    /// let f = DecimalFormatter::usd();
+    /// let mut row = String::new();
+    /// for v in [dec!(1234.5), dec!(-0.126)] {
+    ///     f.write_to(v, &mut row).unwrap();
+    ///     row.push('|');
+    /// }
+    /// assert_eq!(row, "$1,234.50|-$0.13|");
+    /// ```
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, v: Decimal, w: &mut W) -> fmt::Result {
+        match self.validate() {
+            Ok(()) => self.write_valid(v, w),
+            Err(_) => write!(w, "{}", v),
+        }
+    }
+
+    /// Write `v` to `w`, or the empty-value text if None, without
+    /// allocating
+    pub fn write_option_to<W: fmt::Write + ?Sized>(
+        &self,
+        v: Option<Decimal>,
+        w: &mut W,
+    ) -> fmt::Result {
+        match v {
+            Some(v) => self.write_to(v, w),
+            None => self.write_padded_text(&self.empty, w),
+        }
+    }
+
+    /// A value implementing `Display` that formats `v` with this
+    /// formatter when written
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::DecimalFormatter;
+    ///
+    /// let f = DecimalFormatter::separated(1);
+    /// let line = format!("total: {}", f.display(dec!(12345.67)));
+    /// assert_eq!(line, "total: 12,345.7");
+    /// ```
+    pub fn display(&self, v: Decimal) -> Formatted<'_> {
+        Formatted {
+            formatter: self,
+            value: v,
+        }
+    }
+
+    // The symbols must have been validated
+    fn write_valid<W: fmt::Write + ?Sized>(&self, v: Decimal, w: &mut W) -> fmt::Result {
+        let (rounded, min_dp) = match (self.significant_digits, self.dp) {
+            (Some(sf), _) => {
+                let rounded = self.rounding.round_sf(v, sf);
+                let min_dp = msd_exponent(rounded)
+                    .map_or(0, |msd| (sf.max(1) as i32 - 1 - msd).max(0) as u32);
+                (rounded.abs(), min_dp)
+            }
+            (None, Some(dp)) => (self.rounding.round_dp(v, dp).abs(), self.min_dp),
+            (None, None) => (v.abs(), self.min_dp),
+        };
+        let negative = match (&self.zero_style, rounded.is_zero()) {
+            (ZeroStyle::Unsigned, true) => false,
+            (ZeroStyle::Text(text), true) => return self.write_padded_text(text, w),
+            _ => v.is_sign_negative(),
+        };
+
+        let digits = Digits::new(rounded);
+        let integral = digits.integral();
+        let mut fractional = digits.fractional();
+        if self.trim_trailing_zeros {
+            let keep = fractional.trim_end_matches('0').len();
+            let keep = keep.max(min_dp as usize).min(fractional.len());
+            fractional = &fractional[..keep];
+        }
+
+        let (lead, trail) = match (negative, self.sign_style) {
+            (true, SignStyle::Negative | SignStyle::Always) => ("-", ""),
+            (true, SignStyle::Parentheses) => ("(", ")"),
+            (true, SignStyle::TrailingMinus) => ("", "-"),
+            (true, SignStyle::Cr | SignStyle::CrDr) => ("", " CR"),
+            (false, SignStyle::Always) => ("+", ""),
+            (false, SignStyle::CrDr) => ("", " DR"),
+            (false, _) => ("", ""),
+        };
+        let padding = (min_dp as usize).saturating_sub(fractional.len());
+        let fraction_len = match fractional.len() + padding {
+            0 => 0,
+            len => len + 1,
+        };
+
+        // Character counts of the parts before and after the decimal mark
+        // decide how much fill goes on each side
+        let suffix_len = self.suffix.chars().count();
+        let head_len = lead.len()
+            + self.prefix.chars().count()
+            + integral.len()
+            + self.symbols.separator_count(integral.len());
+        let tail_len = fraction_len + suffix_len + trail.len();
+        let (left_fill, right_fill) = match self.alignment {
+            Alignment::Left => (0, self.width.saturating_sub(head_len + tail_len)),
+            Alignment::Right => (self.width.saturating_sub(head_len + tail_len), 0),
+            Alignment::DecimalMark => {
+                let max_fraction_len = match (self.significant_digits, self.dp) {
+                    (None, Some(dp)) if dp.max(min_dp) > 0 => dp.max(min_dp) as usize + 1,
+                    _ => fraction_len,
+                };
+                let max_trail_len = match self.sign_style {
+                    SignStyle::Negative | SignStyle::Always => 0,
+                    SignStyle::Parentheses | SignStyle::TrailingMinus => 1,
+                    SignStyle::Cr | SignStyle::CrDr => 3,
+                };
+                let max_tail_len = max_fraction_len + suffix_len + max_trail_len;
+                let right_fill = max_tail_len.saturating_sub(tail_len);
+                let left_fill = self.width.saturating_sub(head_len + tail_len + right_fill);
+                (left_fill, right_fill)
+            }
+        };
+
+        let zero_fill = self.fill == '0';
+        match self.pad_position {
+            PadPosition::BeforeSign if !zero_fill => {
+                self.write_fill(left_fill, w)?;
+                w.write_str(lead)?;
+                w.write_str(&self.prefix)?;
+            }
+            PadPosition::BeforeSign | PadPosition::AfterSign => {
+                w.write_str(lead)?;
+                w.write_str(&self.prefix)?;
+                self.write_fill(left_fill, w)?;
+            }
+            // Zero padding goes between the sign and the digits
+            PadPosition::AfterPrefix if zero_fill => {
+                w.write_str(&self.prefix)?;
+                w.write_str(lead)?;
+                self.write_fill(left_fill, w)?;
+            }
+            PadPosition::AfterPrefix => {
+                w.write_str(&self.prefix)?;
+                self.write_fill(left_fill, w)?;
+                w.write_str(lead)?;
+            }
+        }
+        self.symbols.write_grouped(w, integral)?;
+        if fraction_len > 0 {
+            w.write_char(self.symbols.decimal_mark)?;
+            w.write_str(fractional)?;
+            for _ in 0..padding {
+                w.write_char('0')?;
+            }
+        }
+        w.write_str(&self.suffix)?;
+        w.write_str(trail)?;
+        self.write_fill(right_fill, w)
+    }
+
+    /// Write `text`, the empty-value or zero text, padded to the width
+    fn write_padded_text<W: fmt::Write + ?Sized>(&self, text: &str, w: &mut W) -> fmt::Result {
+        let fill = self.width.saturating_sub(text.chars().count());
+        match self.alignment {
+            Alignment::Left => {
+                w.write_str(text)?;
+                self.write_fill(fill, w)
+            }
+            Alignment::Right | Alignment::DecimalMark => {
+                self.write_fill(fill, w)?;
+                w.write_str(text)
+            }
+        }
+    }
+
+    fn padded_empty(&self) -> String {
+        let mut s = String::with_capacity(self.empty.len() + self.width);
+        // Writing to a String never fails
+        let _ = self.write_padded_text(&self.empty, &mut s);
+        s
+    }
+
+    fn write_fill<W: fmt::Write + ?Sized>(&self, n: usize, w: &mut W) -> fmt::Result {
+        for _ in 0..n {
+            w.write_char(self.fill)?;
+        }
+        Ok(())
+    }
+
+    /// Format `v` or return the empty-value text if None
+    pub fn try_format_option(&self, v: Option<Decimal>) -> Result<String, DecUtilsError> {
+        match v {
+            Some(v) => self.try_format(v),
+            None => self.validate().map(|_| self.padded_empty()),
+        }
+    }
+
+    /// Parse a string produced by this formatter back to a decimal
+    ///
+    /// Surrounding whitespace is ignored and the prefix and suffix are
+    /// optional. Every `SignStyle` is accepted whatever the formatter's
+    /// style is, a leading "-" or "+" may be before or after the prefix.
+    /// The result is exact, a string with more digits than a `Decimal`
+    /// can hold is an error rather than being rounded.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::{iso, DecimalFormatter};
+    ///
+    /// let f = DecimalFormatter::money(iso::EUR);
+    /// assert_eq!(f.parse("-€1.234,50"), Ok(dec!(-1234.50)));
+    /// assert_eq!(f.parse(" 1.234 "), Ok(dec!(1234)));
+    /// assert!(f.parse("1,234.50").is_err());
+    ///
+    /// let v = dec!(-9876543.21);
+    /// assert_eq!(f.parse(&f.format(v)), Ok(v));
+    /// ```
+    pub fn parse(&self, s: &str) -> Result<Decimal, DecUtilsError> {
+        self.validate()?;
+        let invalid = || DecUtilsError::InvalidNumber(s.to_owned());
+
+        let trimmed = self.strip_fill(s);
+        if let ZeroStyle::Text(text) = &self.zero_style {
+            if !text.trim().is_empty() && trimmed == text.trim() {
+                return Ok(Decimal::ZERO);
+            }
+        }
+
+        let (outer, rest) = strip_outer_sign(trimmed);
+        let (leading, rest) = strip_leading_sign(rest);
+        let rest = self.strip_fill(strip_affix(rest, &self.prefix, true));
+        let (leading, rest) = match leading {
+            Some(_) => (leading, rest),
+            None => strip_leading_sign(rest),
+        };
+        let negative = match (outer, leading) {
+            (Some(_), Some(_)) => return Err(invalid()),
+            (Some(negative), None) | (None, Some(negative)) => negative,
+            (None, None) => false,
+        };
+        let rest = self.strip_fill(strip_affix(rest, &self.suffix, false));
+
+        let plain = self.symbols.plain_digits(rest).ok_or_else(invalid)?;
+        let v = Decimal::from_str_exact(&plain)
+            .or_else(|_| {
+                // Trailing fractional zeros, such as the ".00" of a large
+                // USD amount, may not fit in the scale but lose nothing.
+                let trimmed = if plain.contains('.') {
+                    plain.trim_end_matches('0').trim_end_matches('.')
+                } else {
+                    &plain
+                };
+                Decimal::from_str_exact(trimmed)
+            })
+            .map_err(|_| invalid())?;
+
+        Ok(if negative { -v } else { v })
+    }
+
+    /// Parse a string produced by `format_option`, the empty-value text
+    /// or an empty string is None
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::DecimalFormatter;
+    ///
+    /// let f = DecimalFormatter::separated(2).empty("n/a");
+    /// assert_eq!(f.parse_option("n/a"), Ok(None));
+    /// assert_eq!(f.parse_option(""), Ok(None));
+    /// assert_eq!(f.parse_option("1,000.5"), Ok(Some(dec!(1000.5))));
+    /// ```
+    pub fn parse_option(&self, s: &str) -> Result<Option<Decimal>, DecUtilsError> {
+        let trimmed = self.strip_fill(s);
+        if trimmed.is_empty() || trimmed == self.empty.trim() {
+            self.validate()?;
+            Ok(None)
+        } else {
+            self.parse(s).map(Some)
+        }
+    }
+}
+
+impl DecimalFormatter {
+    // The symbols must be unambiguous and the fill can't be mistaken for
+    // part of a value, trailing "0"s would change it
+    fn validate(&self) -> Result<(), DecUtilsError> {
+        self.symbols.validate()?;
+        let fill = self.fill;
+        let collides = !fill.is_whitespace()
+            && (matches!(fill, '-' | '+' | '(' | ')')
+                // Letters of the "CR" and "DR" signs, which parse accepts
+                // in any case
+                || matches!(fill.to_ascii_uppercase(), 'C' | 'R' | 'D')
+                || (fill.is_ascii_digit() && (fill != '0' || self.alignment != Alignment::Right))
+                || fill == self.symbols.decimal_mark
+                || Some(fill) == self.symbols.group_separator);
+        if collides {
+            Err(DecUtilsError::InvalidFill(fill))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Whitespace and a fill that can't be mistaken for a digit, sign or
+    // separator are removed from both ends
+    fn strip_fill<'a>(&self, s: &'a str) -> &'a str {
+        let fill = self.fill;
+        let ambiguous = fill.is_ascii_digit()
+            || matches!(fill, '-' | '+' | '(' | ')')
+            || fill == self.symbols.decimal_mark
+            || Some(fill) == self.symbols.group_separator;
+        if ambiguous {
+            s.trim()
+        } else {
+            s.trim_matches(|c: char| c == fill || c.is_whitespace())
+        }
+    }
+}
+
+/// A decimal and the formatter it is displayed with, returned by
+/// `DecimalFormatter::display`
+#[derive(Clone, Copy, Debug)]
+pub struct Formatted<'a> {
+    formatter: &'a DecimalFormatter,
+    value: Decimal,
+}
+
+impl fmt::Display for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter.write_to(self.value, f)
+    }
+}
+
+/// Strip a leading "-" or "+", the sign is None if there wasn't one
+fn strip_leading_sign(s: &str) -> (Option<bool>, &str) {
+    if let Some(rest) = s.strip_prefix('-') {
+        (Some(true), rest.trim_start())
+    } else if let Some(rest) = s.strip_prefix('+') {
+        (Some(false), rest.trim_start())
+    } else {
+        (None, s)
+    }
+}
+
+/// Strip enclosing parentheses or a trailing "-", "CR" or "DR", the
+/// sign is None if there wasn't one
+fn strip_outer_sign(s: &str) -> (Option<bool>, &str) {
+    let suffix = |marker: &str| {
+        let split = s.len().checked_sub(marker.len())?;
+        match (s.get(..split), s.get(split..)) {
+            (Some(rest), Some(m)) if m.eq_ignore_ascii_case(marker) => Some(rest.trim_end()),
+            _ => None,
+        }
+    };
+
+    if let Some(inner) = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        (Some(true), inner.trim())
+    } else if let Some(rest) = suffix("CR") {
+        (Some(true), rest)
+    } else if let Some(rest) = suffix("DR") {
+        (Some(false), rest)
+    } else if let Some(rest) = suffix("-") {
+        (Some(true), rest)
+    } else {
+        (None, s)
+    }
+}
+
+/// Strip an optional prefix, or suffix, and the whitespace next to it
+fn strip_affix<'a>(s: &'a str, affix: &str, prefix: bool) -> &'a str {
+    let affix = affix.trim();
+    let stripped = if affix.is_empty() {
+        None
+    } else if prefix {
+        s.strip_prefix(affix)
+    } else {
+        s.strip_suffix(affix)
+    };
+    stripped.map_or(s, str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_new() {
+        let f = DecimalFormatter::new();
+        assert_eq!(f.format(dec!(0)), "0");
+        assert_eq!(f.format(dec!(-1234567.8900)), "-1234567.8900");
+        assert_eq!(f.format_option(None), "");
+    }
+
+    #[test]
+    fn test_separated() {
+        let f = DecimalFormatter::separated(2);
+        assert_eq!(f.format(dec!(1.1)), "1.1");
+        assert_eq!(f.format(dec!(123.125)), "123.12");
+        assert_eq!(f.format(dec!(-123456.126)), "-123,456.13");
+        assert_eq!(
+            f.format(Decimal::MAX),
+            "79,228,162,514,264,337,593,543,950,335"
+        );
+    }
+
+    #[test]
+    fn test_usd() {
+        let f = DecimalFormatter::usd();
+        assert_eq!(f.format(dec!(5)), "$5.00");
+        assert_eq!(f.format(dec!(1.1)), "$1.10");
+        assert_eq!(f.format(dec!(-1234567.125)), "-$1,234,567.12");
+        assert_eq!(f, DecimalFormatter::money(iso::USD));
+    }
+
+    #[test]
+    fn test_fixed_dp() {
+        let f = DecimalFormatter::separated(2).fixed_dp(2);
+        assert_eq!(f.format(dec!(5)), "5.00");
+        assert_eq!(f.format(dec!(1.1)), "1.10");
+        assert_eq!(f.format(dec!(-1234.567)), "-1,234.57");
+        assert_eq!(DecimalFormatter::new().fixed_dp(0).format(dec!(1.5)), "2");
+    }
+
+    #[test]
+    fn test_fraction_digits() {
+        let f = DecimalFormatter::new().fraction_digits(0, 3);
+        assert_eq!(f.format(dec!(5.000)), "5");
+        assert_eq!(f.format(dec!(5.1200)), "5.12");
+        assert_eq!(f.format(dec!(5.12345)), "5.123");
+        let f = DecimalFormatter::new().fraction_digits(1, 2);
+        assert_eq!(f.format(dec!(5.000)), "5.0");
+        assert_eq!(f.format(dec!(0.996)), "1.0");
+    }
+
+    #[test]
+    fn test_locale() {
+        let f = DecimalFormatter::new().fixed_dp(2).locale(Locale::DeDe);
+        assert_eq!(f.format(dec!(-1234567.891)), "-1.234.567,89");
+        let f = f.locale(Locale::HiIn).prefix("₹");
+        assert_eq!(f.format(dec!(1234567.891)), "₹12,34,567.89");
+    }
+
+    #[test]
+    fn test_money() {
+        let f = DecimalFormatter::money(iso::EUR);
+        assert_eq!(f.format(dec!(-1234.5)), "-€1.234,50");
+        let f = DecimalFormatter::money(iso::JPY);
+        assert_eq!(f.format(dec!(1234.5)), "¥1,234");
+        let f = DecimalFormatter::money(iso::SEK);
+        assert_eq!(f.format(dec!(1234.5)), "1 234,50 kr");
+        let f = DecimalFormatter::money(iso::CHF);
+        assert_eq!(f.format(dec!(1234.5)), "Fr 1,234.50");
+    }
+
+    #[test]
+    fn test_try_format() {
+        let f = DecimalFormatter::separated(2).decimal_mark(',');
+        assert_eq!(
+            f.try_format(dec!(1234.5)),
+            Err(DecUtilsError::InvalidSymbols {
+                group_separator: Some(','),
+                decimal_mark: ','
+            })
+        );
+        assert_eq!(f.format(dec!(1234.5)), "1234.5");
+        assert!(f.try_format_option(None).is_err());
+        assert_eq!(
+            DecimalFormatter::usd().try_format(dec!(1234.5)),
+            Ok("$1,234.50".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_zero_style() {
+        let f = DecimalFormatter::usd();
+        assert_eq!(f.format(dec!(-0.001)), "$0.00");
+        assert_eq!(f.format(-Decimal::ZERO), "$0.00");
+        let f = f.sign_style(SignStyle::Always);
+        assert_eq!(f.format(dec!(-0.004)), "+$0.00");
+        let f = f.zero_style(ZeroStyle::Signed);
+        assert_eq!(f.format(dec!(-0.004)), "-$0.00");
+        assert_eq!(f.format(dec!(0.004)), "+$0.00");
+        let f = f.zero_style(ZeroStyle::Text("-".to_owned()));
+        assert_eq!(f.format(dec!(-0.004)), "-");
+        assert_eq!(f.format(dec!(-0.005)), "-");
+        assert_eq!(f.format(dec!(-0.006)), "-$0.01");
+    }
+
+    #[test]
+    fn test_parse() {
+        let f = DecimalFormatter::usd();
+        assert_eq!(f.parse("$1,234.56"), Ok(dec!(1234.56)));
+        assert_eq!(f.parse("-$1,234.56"), Ok(dec!(-1234.56)));
+        assert_eq!(f.parse("$-1,234.56"), Ok(dec!(-1234.56)));
+        assert_eq!(f.parse("+$0.10"), Ok(dec!(0.10)));
+        assert_eq!(f.parse("1234"), Ok(dec!(1234)));
+        assert!(f.parse("").is_err());
+        assert!(f.parse("$").is_err());
+        assert!(f.parse("--1").is_err());
+        assert!(f.parse("$1.234,56").is_err());
+        assert!(f.parse("0.00000000000000000000000000001").is_err());
+
+        let f = DecimalFormatter::money(iso::SEK);
+        assert_eq!(f.parse("-1 234,50 kr"), Ok(dec!(-1234.5)));
+
+        let f = DecimalFormatter::separated(2).zero_style(ZeroStyle::Text("—".to_owned()));
+        assert_eq!(f.parse("—"), Ok(Decimal::ZERO));
+        let f = f.decimal_mark(',');
+        assert!(f.parse("1").is_err());
+        assert!(f.parse_option("").is_err());
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let formatters = [
+            DecimalFormatter::new(),
+            DecimalFormatter::separated(2),
+            DecimalFormatter::usd(),
+            DecimalFormatter::money(iso::KWD),
+            DecimalFormatter::money(iso::INR),
+            DecimalFormatter::new().locale(Locale::FrFr).fixed_dp(4),
+            DecimalFormatter::new()
+                .locale(Locale::DeCh)
+                .sign_style(SignStyle::Always)
+                .suffix(" CHF"),
+        ];
+        let values = [
+            dec!(0),
+            dec!(-0.001),
+            dec!(1.1),
+            dec!(-1234567.891),
+            dec!(100000),
+            Decimal::MAX,
+            Decimal::MIN,
+            Decimal::new(1, 28),
+        ];
+        for f in &formatters {
+            for v in values {
+                let s = f.format(v);
+                let parsed = f.parse(&s).unwrap();
+                assert_eq!(f.format(parsed), s);
+                if f.dp.is_none() {
+                    assert_eq!(parsed, v);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sign_styles() {
+        let f = DecimalFormatter::usd();
+        let styles = [
+            (SignStyle::Negative, "-$1,234.56", "$1,234.56"),
+            (SignStyle::Always, "-$1,234.56", "+$1,234.56"),
+            (SignStyle::Parentheses, "($1,234.56)", "$1,234.56"),
+            (SignStyle::TrailingMinus, "$1,234.56-", "$1,234.56"),
+            (SignStyle::Cr, "$1,234.56 CR", "$1,234.56"),
+            (SignStyle::CrDr, "$1,234.56 CR", "$1,234.56 DR"),
+        ];
+        for (style, negative, positive) in styles {
+            let f = f.clone().sign_style(style);
+            assert_eq!(f.format(dec!(-1234.56)), negative);
+            assert_eq!(f.format(dec!(1234.56)), positive);
+            assert_eq!(f.parse(negative), Ok(dec!(-1234.56)));
+            assert_eq!(f.parse(positive), Ok(dec!(1234.56)));
+        }
+
+        let f = DecimalFormatter::money(iso::SEK).sign_style(SignStyle::Parentheses);
+        assert_eq!(f.format(dec!(-1)), "(1,00 kr)");
+    }
+
+    #[test]
+    fn test_parse_sign_forms() {
+        let f = DecimalFormatter::separated(2);
+        assert_eq!(f.parse("(1,234.56)"), Ok(dec!(-1234.56)));
+        assert_eq!(f.parse("( 1,234.56 )"), Ok(dec!(-1234.56)));
+        assert_eq!(f.parse("1,234.56-"), Ok(dec!(-1234.56)));
+        assert_eq!(f.parse("1,234.56cr"), Ok(dec!(-1234.56)));
+        assert_eq!(f.parse("1,234.56 Dr"), Ok(dec!(1234.56)));
+        assert!(f.parse("(-1)").is_err());
+        assert!(f.parse("-1-").is_err());
+        assert!(f.parse("+1 CR").is_err());
+        assert!(f.parse("(1").is_err());
+        assert!(f.parse("1)").is_err());
+        assert!(f.parse("CR").is_err());
+        assert!(f.parse("-").is_err());
+    }
+
+    #[test]
+    fn test_significant_digits() {
+        let f = DecimalFormatter::separated(2).significant_digits(3);
+        assert_eq!(f.format(dec!(0)), "0");
+        assert_eq!(f.format(dec!(-0.0004)), "-0.000400");
+        assert_eq!(f.format(dec!(0.99996)), "1.00");
+        assert_eq!(f.format(dec!(-99.96)), "-100");
+        assert_eq!(f.format(dec!(65_000)), "65,000");
+        assert_eq!(f.format(dec!(1_234_567)), "1,230,000");
+        assert_eq!(
+            f.clone().group_separator(None).format(dec!(1_234_567)),
+            "1230000"
+        );
+        assert_eq!(f.clone().dp(2).format(dec!(0.0004)), "0.00");
+        assert_eq!(
+            f.format(Decimal::MAX),
+            "79,200,000,000,000,000,000,000,000,000"
+        );
+        assert_eq!(
+            f.format(Decimal::new(123456, 28)),
+            "0.0000000000000000000000123"
+        );
+    }
+
+    #[test]
+    fn test_rounding() {
+        let f = DecimalFormatter::new().dp(0);
+        let r = |s| f.clone().rounding(s);
+        assert_eq!(f.format(dec!(2.5)), "2");
+        assert_eq!(
+            r(RoundingMode::MidpointAwayFromZero).format(dec!(-2.5)),
+            "-3"
+        );
+        assert_eq!(r(RoundingMode::ToNegativeInfinity).format(dec!(-2.1)), "-3");
+        assert_eq!(r(RoundingMode::ToPositiveInfinity).format(dec!(-2.9)), "-2");
+        assert_eq!(r(RoundingMode::ToPositiveInfinity).format(dec!(2.1)), "3");
+    }
+
+    #[test]
+    fn test_sign_prefix_suffix() {
+        let f = DecimalFormatter::separated(1)
+            .sign_style(SignStyle::Always)
+            .prefix("$")
+            .suffix(" USD");
+        assert_eq!(f.format(dec!(1000)), "+$1,000 USD");
+        assert_eq!(f.format(dec!(-1000.25)), "-$1,000.2 USD");
+    }
+
+    #[test]
+    fn test_write_to() {
+        let f = DecimalFormatter::separated(2)
+            .sign_style(SignStyle::Parentheses)
+            .empty("-");
+        let mut s = String::new();
+        f.write_to(dec!(-1234.5), &mut s).unwrap();
+        f.write_option_to(None, &mut s).unwrap();
+        assert_eq!(s, "(1,234.5)-");
+        assert_eq!(f.display(dec!(0.001)).to_string(), f.format(dec!(0.001)));
+
+        let f = f.zero_style(ZeroStyle::Text("nil".to_owned()));
+        assert_eq!(f.display(dec!(0.001)).to_string(), "nil");
+
+        // An invalid formatter falls back to Display like format does
+        let f = f.decimal_mark(',').group_separator(Some(','));
+        assert_eq!(f.display(dec!(-1234.5)).to_string(), "-1234.5");
+    }
+
+    #[test]
+    fn test_width_and_alignment() {
+        let f = DecimalFormatter::usd().width(12);
+        assert_eq!(f.format(dec!(-12.3)), "     -$12.30");
+        assert_eq!(f.format(dec!(1234567.891)), "$1,234,567.89");
+        assert_eq!(f.clone().width(3).format(dec!(1)), "$1.00");
+        assert_eq!(
+            f.clone().align(Alignment::Left).format(dec!(1)),
+            "$1.00       "
+        );
+        assert_eq!(f.clone().empty("n/a").format_option(None), "         n/a");
+        assert_eq!(
+            f.clone()
+                .fill('0')
+                .pad_position(PadPosition::AfterSign)
+                .format(dec!(-1)),
+            "-$0000001.00"
+        );
+
+        let f = DecimalFormatter::money(iso::EUR)
+            .width(10)
+            .pad_position(PadPosition::AfterPrefix)
+            .sign_style(SignStyle::Parentheses);
+        assert_eq!(f.format(dec!(-1234.5)), "€(1.234,50)");
+        assert_eq!(f.format(dec!(-1.5)), "€   (1,50)");
+        assert_eq!(f.format(dec!(1.5)), "€     1,50");
+
+        let f = DecimalFormatter::separated(2)
+            .zero_style(ZeroStyle::Text("-".to_owned()))
+            .width(4);
+        assert_eq!(f.format(dec!(0)), "   -");
+        assert_eq!(f.align(Alignment::Left).format(dec!(0)), "-   ");
+    }
+
+    #[test]
+    fn test_decimal_mark_alignment() {
+        let f = DecimalFormatter::new()
+            .fraction_digits(0, 3)
+            .group_separator(Some(','))
+            .sign_style(SignStyle::Cr)
+            .suffix(" kg")
+            .width(16)
+            .align(Alignment::DecimalMark);
+        let column: Vec<String> = [dec!(1), dec!(-1234.5), dec!(0.125), dec!(-12.25)]
+            .iter()
+            .map(|&v| f.format(v))
+            .collect();
+        assert_eq!(
+            column,
+            [
+                "     1 kg       ",
+                " 1,234.5 kg CR  ",
+                "     0.125 kg   ",
+                "    12.25 kg CR ",
+            ]
+        );
+        for line in &column {
+            assert_eq!(line.chars().count(), 16);
+        }
+
+        // Significant digits have no fixed number of fractional digits
+        let f = DecimalFormatter::new()
+            .significant_digits(2)
+            .width(6)
+            .align(Alignment::DecimalMark);
+        assert_eq!(f.format(dec!(1.25)), "   1.2");
+    }
+
+    #[test]
+    fn test_parse_padded() {
+        let f = DecimalFormatter::usd()
+            .width(12)
+            .fill('*')
+            .pad_position(PadPosition::AfterPrefix)
+            .empty("n/a");
+        for v in [dec!(-12.3), dec!(0), dec!(1234567.89)] {
+            assert_eq!(f.parse(&f.format(v)), Ok(v));
+        }
+        assert_eq!(f.format(dec!(-12.3)), "$*****-12.30");
+        assert_eq!(f.parse_option(&f.format_option(None)), Ok(None));
+
+        let f = f.align(Alignment::Left);
+        assert_eq!(f.parse(&f.format(dec!(-12.3))), Ok(dec!(-12.3)));
+
+        // Zero padding always follows the sign
+        let f = DecimalFormatter::usd().width(10).fill('0');
+        assert_eq!(f.format(dec!(-1)), "-$00001.00");
+        let f = f.pad_position(PadPosition::AfterPrefix);
+        assert_eq!(f.format(dec!(-1)), "$-00001.00");
+        for v in [dec!(-1), dec!(0), dec!(12.5), dec!(1234567.89)] {
+            assert_eq!(f.parse(&f.format(v)), Ok(v));
+        }
+        let f = f.sign_style(SignStyle::Parentheses);
+        assert_eq!(f.format(dec!(-1)), "$(0001.00)");
+
+        // Fills that can be mistaken for part of the value are rejected
+        let f = DecimalFormatter::separated(2).width(10);
+        for fill in ['-', '+', '(', ',', '.', '7', 'C', 'r', 'D'] {
+            let f = f.clone().fill(fill);
+            assert_eq!(f.try_format(dec!(1)), Err(DecUtilsError::InvalidFill(fill)));
+            assert_eq!(f.parse("1"), Err(DecUtilsError::InvalidFill(fill)));
+        }
+        let f = f.fill('0').align(Alignment::Left);
+        assert_eq!(f.try_format(dec!(1)), Err(DecUtilsError::InvalidFill('0')));
+    }
+}
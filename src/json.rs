@@ -0,0 +1,120 @@
+//! Lossless conversion between `Decimal` and serde_json numbers
+//!
+//! The crate enables serde_json's arbitrary precision, so a `Number`
+//! keeps the exact digits of the JSON text and nothing here goes
+//! through `f64`.
+//!
+//! # Example
+//! ```
+//! use rust_decimal_macros::dec;
+//! use serde_json::Value;
+//!
+//! use dec_utils::{json, DecUtilsError};
+//!
+//! let v: Value = serde_json::from_str(r#"{"price": 12345.678901234567890123, "qty": 1e-30}"#).unwrap();
+//! assert_eq!(json::from_value(&v["price"]), Ok(dec!(12345.678901234567890123)));
+//! assert_eq!(
+//!     json::from_value(&v["qty"]),
+//!     Err(DecUtilsError::ExcessiveScale("1e-30".to_owned()))
+//! );
+//!
+//! let out = serde_json::json!({ "total": json::to_value(dec!(0.1000000000000000000000000001)) });
+//! assert_eq!(out.to_string(), r#"{"total":0.1000000000000000000000000001}"#);
+//! ```
+use rust_decimal::prelude::*;
+use serde_json::{Number, Value};
+
+use crate::{DecUtilsError, ScientificFormatter};
+
+/// Convert `n` to a decimal exactly
+///
+/// Returns `DecUtilsError::OutOfRange` if `n` is too large and
+/// `DecUtilsError::ExcessiveScale` if it has too many decimal places.
+pub fn from_number(n: &Number) -> Result<Decimal, DecUtilsError> {
+    ScientificFormatter::parse(n.as_str())
+}
+
+/// Convert a JSON number, or a string holding a plain or scientific
+/// number as many APIs send, to a decimal exactly
+///
+/// Any other JSON value is `DecUtilsError::InvalidNumber`, the errors
+/// for numbers are those of `from_number`.
+pub fn from_value(v: &Value) -> Result<Decimal, DecUtilsError> {
+    match v {
+        Value::Number(n) => from_number(n),
+        Value::String(s) => ScientificFormatter::parse(s),
+        _ => Err(DecUtilsError::InvalidNumber(v.to_string())),
+    }
+}
+
+/// Convert `v` to a JSON number with exactly its digits, including
+/// trailing fractional zeros
+pub fn to_number(v: Decimal) -> Number {
+    // Decimal's Display is always a valid JSON number, so the fallback
+    // is never used
+    v.to_string().parse().unwrap_or_else(|_| Number::from(0))
+}
+
+/// Convert `v` to a JSON number value, see `to_number`
+pub fn to_value(v: Decimal) -> Value {
+    Value::Number(to_number(v))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_from_value() {
+        let v: Value = serde_json::from_str(
+            r#"[0, -0.0, 1.50, -7.9228162514264337593543950335e28, 1E-28,
+                "12.5", "2.5e3", 79228162514264337593543950336,
+                0.00000000000000000000000000001, null, true, "abc", [1]]"#,
+        )
+        .unwrap();
+        let results: Vec<_> = v.as_array().unwrap().iter().map(from_value).collect();
+        assert_eq!(
+            results,
+            [
+                Ok(dec!(0)),
+                Ok(dec!(0)),
+                Ok(dec!(1.50)),
+                Ok(Decimal::MIN),
+                Ok(Decimal::new(1, 28)),
+                Ok(dec!(12.5)),
+                Ok(dec!(2500)),
+                Err(DecUtilsError::OutOfRange(
+                    "79228162514264337593543950336".to_owned()
+                )),
+                Err(DecUtilsError::ExcessiveScale(
+                    "0.00000000000000000000000000001".to_owned()
+                )),
+                Err(DecUtilsError::InvalidNumber("null".to_owned())),
+                Err(DecUtilsError::InvalidNumber("true".to_owned())),
+                Err(DecUtilsError::InvalidNumber("abc".to_owned())),
+                Err(DecUtilsError::InvalidNumber("[1]".to_owned())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_value() {
+        let values = [
+            dec!(0),
+            dec!(-1.50),
+            Decimal::MAX,
+            Decimal::MIN,
+            Decimal::new(1, 28),
+            Decimal::from_i128_with_scale(Decimal::MAX.mantissa(), 28),
+        ];
+        for v in values {
+            let json = serde_json::to_string(&to_value(v)).unwrap();
+            assert_eq!(json, v.to_string());
+            let parsed: Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(from_value(&parsed), Ok(v));
+            assert_eq!(from_value(&parsed).unwrap().scale(), v.scale());
+        }
+    }
+}
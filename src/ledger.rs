@@ -0,0 +1,631 @@
+use std::cmp::Ordering;
+
+use rust_decimal::prelude::*;
+
+use crate::DecUtilsError;
+
+/// Which lots a sale is matched against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CostBasisMethod {
+    /// First in, first out, the oldest lots are sold first
+    #[default]
+    Fifo,
+
+    /// Last in, first out, the newest lots are sold first
+    Lifo,
+
+    /// Highest in, first out, the lots with the highest unit cost are
+    /// sold first, the oldest of equal lots first
+    Hifo,
+
+    /// Every purchase is pooled into a single lot at its average cost
+    AverageCost,
+}
+
+/// A quantity bought together and its cost basis
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Lot {
+    /// The index of the buy event that opened the lot, the first for a
+    /// pooled average cost lot
+    pub event: usize,
+
+    /// The units still held
+    pub qty: Decimal,
+
+    /// The total cost including fees
+    pub cost: Decimal,
+}
+
+impl Lot {
+    /// The cost of one unit, unrounded
+    pub fn unit_cost(&self) -> Decimal {
+        self.cost.checked_div(self.qty).unwrap_or_default()
+    }
+}
+
+/// An event consumed by a `Ledger`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LedgerEvent {
+    /// Buy `qty` at `price` per unit paying `fee`, the fee is added to
+    /// the cost basis
+    Buy {
+        qty: Decimal,
+        price: Decimal,
+        fee: Decimal,
+    },
+
+    /// Sell `qty` at `price` per unit paying `fee`, the fee is deducted
+    /// from the proceeds
+    Sell {
+        qty: Decimal,
+        price: Decimal,
+        fee: Decimal,
+    },
+
+    /// Pay a fee of `qty` units of the asset itself, e.g. a network
+    /// fee. It's matched like a sale with no proceeds so its cost basis
+    /// is realized as a loss.
+    Fee { qty: Decimal },
+}
+
+/// The result of a sale or fee event
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Disposal {
+    /// The index of the event
+    pub event: usize,
+
+    /// The units sold or paid as a fee
+    pub qty: Decimal,
+
+    /// The sale value less fees
+    pub proceeds: Decimal,
+
+    /// The cost of the lots sold
+    pub cost_basis: Decimal,
+
+    /// `proceeds - cost_basis`, negative for a loss
+    pub gain: Decimal,
+
+    /// The parts of lots sold, in the order they were matched
+    pub lots: Vec<Lot>,
+}
+
+/// Tracks the lots of one asset and the gains realized by selling them
+///
+/// Events are applied in the order they happened. Costs, proceeds and
+/// totals are exact, an event whose amounts would have to be rounded
+/// fails instead. When part of a lot is sold its cost is split in
+/// proportion to the quantity, which rounds to 28 significant digits,
+/// and the remaining part keeps the rest, so the cost basis of
+/// everything sold plus that of the remaining lots always equals
+/// exactly what was paid.
+/// Round for display, e.g. with `dec_to_usd_string`.
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_usd_string, CostBasisMethod, Ledger};
+///
+/// let mut ledger = Ledger::new(CostBasisMethod::Fifo);
+/// ledger.buy(dec!(1), dec!(20000), dec!(10)).unwrap();
+/// ledger.buy(dec!(1), dec!(30000), dec!(15)).unwrap();
+/// let sale = ledger.sell(dec!(1.5), dec!(40000), dec!(30)).unwrap();
+///
+/// assert_eq!(dec_to_usd_string(sale.proceeds), "$59,970.00");
+/// assert_eq!(dec_to_usd_string(sale.cost_basis), "$35,017.50");
+/// assert_eq!(dec_to_usd_string(ledger.realized_gain()), "$24,952.50");
+/// assert_eq!(ledger.qty(), dec!(0.5));
+/// assert_eq!(dec_to_usd_string(ledger.cost_basis()), "$15,007.50");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ledger {
+    method: CostBasisMethod,
+    lots: Vec<Lot>,
+    disposals: Vec<Disposal>,
+    realized_gain: Decimal,
+    events: usize,
+}
+
+impl Ledger {
+    /// An empty ledger matching sales with `method`
+    pub fn new(method: CostBasisMethod) -> Self {
+        Self {
+            method,
+            ..Self::default()
+        }
+    }
+
+    /// The cost basis method
+    pub fn method(&self) -> CostBasisMethod {
+        self.method
+    }
+
+    /// Apply `event`, returning the disposal of a sale or fee
+    ///
+    /// Returns `DecUtilsError::InvalidQuantity` if a quantity isn't
+    /// positive, `DecUtilsError::InvalidPrice` or
+    /// `DecUtilsError::InvalidFee` if a price or fee is negative,
+    /// `DecUtilsError::InsufficientLots` if more is sold than is held
+    /// and `DecUtilsError::Overflow` if an amount or a running total
+    /// can't be computed exactly. The ledger is unchanged by a failed
+    /// event.
+    pub fn apply(&mut self, event: LedgerEvent) -> Result<Option<Disposal>, DecUtilsError> {
+        let disposal = match event {
+            LedgerEvent::Buy { qty, price, fee } => {
+                self.open(qty, price, fee)?;
+                None
+            }
+            LedgerEvent::Sell { qty, price, fee } => {
+                check_amounts(qty, price, fee)?;
+                let proceeds = exact_mul(qty, price)
+                    .and_then(|v| exact_add(v, -fee))
+                    .ok_or(DecUtilsError::Overflow)?;
+                Some(self.dispose(qty, proceeds)?)
+            }
+            LedgerEvent::Fee { qty } => {
+                check_amounts(qty, Decimal::ZERO, Decimal::ZERO)?;
+                Some(self.dispose(qty, Decimal::ZERO)?)
+            }
+        };
+        self.events += 1;
+        Ok(disposal)
+    }
+
+    /// Apply each of `events` in order, stopping at the first error
+    pub fn apply_all<I>(&mut self, events: I) -> Result<(), DecUtilsError>
+    where
+        I: IntoIterator<Item = LedgerEvent>,
+    {
+        events
+            .into_iter()
+            .try_for_each(|event| self.apply(event).map(|_| ()))
+    }
+
+    /// Apply a buy event, see `apply`
+    pub fn buy(&mut self, qty: Decimal, price: Decimal, fee: Decimal) -> Result<(), DecUtilsError> {
+        self.apply(LedgerEvent::Buy { qty, price, fee }).map(|_| ())
+    }
+
+    /// Apply a sell event, see `apply`
+    pub fn sell(
+        &mut self,
+        qty: Decimal,
+        price: Decimal,
+        fee: Decimal,
+    ) -> Result<Disposal, DecUtilsError> {
+        let disposal = self.apply(LedgerEvent::Sell { qty, price, fee })?;
+        // A sell event always has a disposal
+        Ok(disposal.unwrap_or_default())
+    }
+
+    /// Apply a fee event, see `apply`
+    pub fn fee(&mut self, qty: Decimal) -> Result<Disposal, DecUtilsError> {
+        let disposal = self.apply(LedgerEvent::Fee { qty })?;
+        // A fee event always has a disposal
+        Ok(disposal.unwrap_or_default())
+    }
+
+    /// The lots held, oldest first
+    pub fn lots(&self) -> &[Lot] {
+        &self.lots
+    }
+
+    /// Every sale and fee so far
+    pub fn disposals(&self) -> &[Disposal] {
+        &self.disposals
+    }
+
+    /// The quantity held
+    pub fn qty(&self) -> Decimal {
+        // Buys check the totals fit, so these sums never overflow
+        self.lots.iter().map(|lot| lot.qty).sum()
+    }
+
+    /// The cost basis of the quantity held
+    pub fn cost_basis(&self) -> Decimal {
+        self.lots.iter().map(|lot| lot.cost).sum()
+    }
+
+    /// The total gain of every sale and fee, negative for a loss
+    pub fn realized_gain(&self) -> Decimal {
+        self.realized_gain
+    }
+
+    fn open(&mut self, qty: Decimal, price: Decimal, fee: Decimal) -> Result<(), DecUtilsError> {
+        check_amounts(qty, price, fee)?;
+        let cost = exact_mul(qty, price)
+            .and_then(|v| exact_add(v, fee))
+            .ok_or(DecUtilsError::Overflow)?;
+        // Totals that had to be rounded would let sales match more or
+        // less than was bought
+        let total_qty = exact_add(self.qty(), qty);
+        let total_cost = exact_add(self.cost_basis(), cost);
+        let (Some(total_qty), Some(total_cost)) = (total_qty, total_cost) else {
+            return Err(DecUtilsError::Overflow);
+        };
+
+        match (self.method, self.lots.first_mut()) {
+            (CostBasisMethod::AverageCost, Some(pool)) => {
+                pool.qty = total_qty;
+                pool.cost = total_cost;
+            }
+            _ => self.lots.push(Lot {
+                event: self.events,
+                qty,
+                cost,
+            }),
+        }
+        Ok(())
+    }
+
+    fn dispose(&mut self, qty: Decimal, proceeds: Decimal) -> Result<Disposal, DecUtilsError> {
+        let held = self.qty();
+        if qty > held {
+            return Err(DecUtilsError::InsufficientLots { held, qty });
+        }
+
+        // Work on a copy so a failure leaves the ledger unchanged
+        let mut lots = self.lots.clone();
+        let mut matched = Vec::new();
+        let mut rest = qty;
+        while !rest.is_zero() {
+            if lots.is_empty() {
+                return Err(DecUtilsError::InsufficientLots { held, qty });
+            }
+            let i = self.next_lot(&lots);
+            let part = rest.min(lots[i].qty);
+            matched.push(take(&mut lots, i, part)?);
+            rest -= part;
+        }
+        let cost_basis = matched
+            .iter()
+            .try_fold(Decimal::ZERO, |sum, lot| exact_add(sum, lot.cost))
+            .ok_or(DecUtilsError::Overflow)?;
+        let gain = exact_add(proceeds, -cost_basis).ok_or(DecUtilsError::Overflow)?;
+        let realized_gain = exact_add(self.realized_gain, gain).ok_or(DecUtilsError::Overflow)?;
+
+        let disposal = Disposal {
+            event: self.events,
+            qty,
+            proceeds,
+            cost_basis,
+            gain,
+            lots: matched,
+        };
+        self.lots = lots;
+        self.realized_gain = realized_gain;
+        self.disposals.push(disposal.clone());
+        Ok(disposal)
+    }
+
+    /// The index of the lot to sell from next, `lots` is never empty
+    fn next_lot(&self, lots: &[Lot]) -> usize {
+        match self.method {
+            CostBasisMethod::Fifo | CostBasisMethod::AverageCost => 0,
+            CostBasisMethod::Lifo => lots.len() - 1,
+            CostBasisMethod::Hifo => {
+                let mut highest = 0;
+                for (i, lot) in lots.iter().enumerate().skip(1) {
+                    if cmp_unit_cost(lot, &lots[highest]) == Ordering::Greater {
+                        highest = i;
+                    }
+                }
+                highest
+            }
+        }
+    }
+}
+
+/// Remove `qty` from `lots[i]`, returning the part removed with its share
+/// of the cost, which may be rounded. The lot keeps the rest of the cost,
+/// so the two always sum exactly to the original.
+fn take(lots: &mut Vec<Lot>, i: usize, qty: Decimal) -> Result<Lot, DecUtilsError> {
+    if qty == lots[i].qty {
+        return Ok(lots.remove(i));
+    }
+    let lot = &mut lots[i];
+    // Multiplying first is exact more often, dividing first can't overflow
+    let share = lot
+        .cost
+        .checked_mul(qty)
+        .and_then(|v| v.checked_div(lot.qty))
+        .or_else(|| {
+            qty.checked_div(lot.qty)
+                .and_then(|f| lot.cost.checked_mul(f))
+        })
+        .ok_or(DecUtilsError::Overflow)?;
+    // The rest may be rounded too, taking the part's cost as the
+    // difference keeps the two summing exactly to the original
+    let rest_cost = lot.cost.checked_sub(share).ok_or(DecUtilsError::Overflow)?;
+    let (Some(rest_qty), Some(cost)) = (exact_add(lot.qty, -qty), exact_add(lot.cost, -rest_cost))
+    else {
+        return Err(DecUtilsError::Overflow);
+    };
+    lot.qty = rest_qty;
+    lot.cost = rest_cost;
+    Ok(Lot {
+        event: lot.event,
+        qty,
+        cost,
+    })
+}
+
+/// `a + b` if the sum is exact, None if it overflows or is rounded
+fn exact_add(a: Decimal, b: Decimal) -> Option<Decimal> {
+    let sum = a.checked_add(b)?;
+    (sum - a == b && sum - b == a).then_some(sum)
+}
+
+/// `a * b` if the product is exact, None if it overflows or is rounded
+fn exact_mul(a: Decimal, b: Decimal) -> Option<Decimal> {
+    let (a, b) = (a.normalize(), b.normalize());
+    let product = a.checked_mul(b)?;
+    // A product is only shortened when its digits don't fit, so a
+    // smaller scale means some were dropped. Rarely those are all zeros
+    // and an exact product is rejected too.
+    (a.is_zero() || b.is_zero() || product.scale() == a.scale() + b.scale()).then_some(product)
+}
+
+/// Compare unit costs exactly by cross multiplying when possible
+fn cmp_unit_cost(a: &Lot, b: &Lot) -> Ordering {
+    match (a.cost.checked_mul(b.qty), b.cost.checked_mul(a.qty)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.unit_cost().cmp(&b.unit_cost()),
+    }
+}
+
+/// Check the quantity is positive and the price and fee aren't negative
+fn check_amounts(qty: Decimal, price: Decimal, fee: Decimal) -> Result<(), DecUtilsError> {
+    if qty.is_sign_negative() || qty.is_zero() {
+        return Err(DecUtilsError::InvalidQuantity(qty));
+    }
+    if price.is_sign_negative() && !price.is_zero() {
+        return Err(DecUtilsError::InvalidPrice(price));
+    }
+    if fee.is_sign_negative() && !fee.is_zero() {
+        return Err(DecUtilsError::InvalidFee(fee));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn ledger(method: CostBasisMethod) -> Ledger {
+        let mut ledger = Ledger::new(method);
+        ledger
+            .apply_all([
+                LedgerEvent::Buy {
+                    qty: dec!(1),
+                    price: dec!(100),
+                    fee: dec!(0),
+                },
+                LedgerEvent::Buy {
+                    qty: dec!(1),
+                    price: dec!(200),
+                    fee: dec!(2),
+                },
+                LedgerEvent::Buy {
+                    qty: dec!(1),
+                    price: dec!(150),
+                    fee: dec!(0),
+                },
+            ])
+            .unwrap();
+        ledger
+    }
+
+    fn lots(ledger: &Ledger) -> Vec<(usize, Decimal, Decimal)> {
+        ledger
+            .lots()
+            .iter()
+            .map(|lot| (lot.event, lot.qty, lot.cost))
+            .collect()
+    }
+
+    #[test]
+    fn test_methods() {
+        let cases = [
+            (
+                CostBasisMethod::Fifo,
+                dec!(201),
+                vec![(1, dec!(0.5), dec!(101)), (2, dec!(1), dec!(150))],
+            ),
+            (
+                CostBasisMethod::Lifo,
+                dec!(251),
+                vec![(0, dec!(1), dec!(100)), (1, dec!(0.5), dec!(101))],
+            ),
+            (
+                CostBasisMethod::Hifo,
+                dec!(277),
+                vec![(0, dec!(1), dec!(100)), (2, dec!(0.5), dec!(75))],
+            ),
+            (
+                CostBasisMethod::AverageCost,
+                dec!(226),
+                vec![(0, dec!(1.5), dec!(226))],
+            ),
+        ];
+        for (method, cost_basis, remaining) in cases {
+            let mut ledger = ledger(method);
+            let sale = ledger.sell(dec!(1.5), dec!(300), dec!(3)).unwrap();
+            assert_eq!(sale.event, 3);
+            assert_eq!(sale.proceeds, dec!(447));
+            assert_eq!(sale.cost_basis, cost_basis, "{:?}", method);
+            assert_eq!(sale.gain, dec!(447) - cost_basis);
+            assert_eq!(sale.lots.iter().map(|l| l.qty).sum::<Decimal>(), dec!(1.5));
+            assert_eq!(ledger.realized_gain(), sale.gain);
+            assert_eq!(lots(&ledger), remaining, "{:?}", method);
+            assert_eq!(ledger.qty(), dec!(1.5));
+            assert_eq!(ledger.cost_basis(), dec!(452) - cost_basis);
+        }
+    }
+
+    #[test]
+    fn test_matched_lots() {
+        let mut ledger = ledger(CostBasisMethod::Hifo);
+        let sale = ledger.sell(dec!(1.5), dec!(300), dec!(3)).unwrap();
+        assert_eq!(
+            sale.lots,
+            [
+                Lot {
+                    event: 1,
+                    qty: dec!(1),
+                    cost: dec!(202)
+                },
+                Lot {
+                    event: 2,
+                    qty: dec!(0.5),
+                    cost: dec!(75)
+                },
+            ]
+        );
+        assert_eq!(ledger.lots()[1].unit_cost(), dec!(150));
+    }
+
+    #[test]
+    fn test_fee() {
+        let mut ledger = ledger(CostBasisMethod::Fifo);
+        let fee = ledger.fee(dec!(0.1)).unwrap();
+        assert_eq!(fee.proceeds, dec!(0));
+        assert_eq!(fee.cost_basis, dec!(10));
+        assert_eq!(fee.gain, dec!(-10));
+        assert_eq!(ledger.qty(), dec!(2.9));
+        assert_eq!(ledger.realized_gain(), dec!(-10));
+        assert_eq!(ledger.disposals(), [fee]);
+    }
+
+    #[test]
+    fn test_cost_is_conserved() {
+        let methods = [
+            CostBasisMethod::Fifo,
+            CostBasisMethod::Lifo,
+            CostBasisMethod::Hifo,
+            CostBasisMethod::AverageCost,
+        ];
+        for method in methods {
+            let mut ledger = Ledger::new(method);
+            ledger.buy(dec!(3), dec!(10), dec!(0.01)).unwrap();
+            ledger.buy(dec!(7), dec!(3.33), dec!(0.07)).unwrap();
+            ledger.sell(dec!(1), dec!(12), dec!(0)).unwrap();
+            ledger.fee(dec!(0.001)).unwrap();
+            ledger.sell(dec!(2.5), dec!(9), dec!(0.5)).unwrap();
+            ledger.buy(dec!(0.3), dec!(11), dec!(0)).unwrap();
+            ledger.sell(dec!(1.7), dec!(13), dec!(0.1)).unwrap();
+
+            let paid = dec!(30.01) + dec!(23.38) + dec!(3.3);
+            let sold: Decimal = ledger.disposals().iter().map(|d| d.cost_basis).sum();
+            assert_eq!(sold + ledger.cost_basis(), paid, "{:?}", method);
+            let proceeds: Decimal = ledger.disposals().iter().map(|d| d.proceeds).sum();
+            assert_eq!(ledger.realized_gain(), proceeds - sold);
+
+            let held = ledger.qty();
+            let last = ledger.sell(held, dec!(10), dec!(0)).unwrap();
+            assert!(ledger.lots().is_empty());
+            assert_eq!(ledger.cost_basis(), dec!(0));
+            assert_eq!(sold + last.cost_basis, paid, "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut ledger = ledger(CostBasisMethod::Fifo);
+        let before = ledger.clone();
+        assert_eq!(
+            ledger.sell(dec!(3.5), dec!(1), dec!(0)),
+            Err(DecUtilsError::InsufficientLots {
+                held: dec!(3),
+                qty: dec!(3.5)
+            })
+        );
+        assert_eq!(
+            ledger.buy(dec!(0), dec!(1), dec!(0)),
+            Err(DecUtilsError::InvalidQuantity(dec!(0)))
+        );
+        assert_eq!(
+            ledger.buy(dec!(1), dec!(-1), dec!(0)),
+            Err(DecUtilsError::InvalidPrice(dec!(-1)))
+        );
+        assert_eq!(
+            ledger.sell(dec!(1), dec!(1), dec!(-1)),
+            Err(DecUtilsError::InvalidFee(dec!(-1)))
+        );
+        assert_eq!(
+            ledger.fee(dec!(-1)),
+            Err(DecUtilsError::InvalidQuantity(dec!(-1)))
+        );
+        assert_eq!(
+            ledger.buy(Decimal::MAX, dec!(1), dec!(0)),
+            Err(DecUtilsError::Overflow)
+        );
+        assert_eq!(ledger, before);
+        assert_eq!(
+            ledger.sell(dec!(1), Decimal::MAX, dec!(0)).map(|_| ()),
+            Ok(())
+        );
+        assert_eq!(
+            ledger.sell(dec!(1), Decimal::MAX, dec!(0)).map(|_| ()),
+            Err(DecUtilsError::Overflow)
+        );
+        assert_eq!(ledger.qty(), dec!(2));
+        assert_eq!(ledger.disposals().len(), 1);
+    }
+
+    #[test]
+    fn test_inexact_products() {
+        let mut ledger = Ledger::new(CostBasisMethod::Fifo);
+        // The exact cost has 32 decimal places
+        assert_eq!(
+            ledger.buy(dec!(0.3333333333333333), dec!(0.3333333333333333), dec!(0)),
+            Err(DecUtilsError::Overflow)
+        );
+        // 10^-29 would be rounded to zero
+        assert_eq!(
+            ledger.buy(dec!(0.00000000000001), dec!(0.000000000000001), dec!(0)),
+            Err(DecUtilsError::Overflow)
+        );
+        assert!(ledger.lots().is_empty());
+
+        ledger.buy(dec!(3), dec!(100), dec!(1)).unwrap();
+        // 301 / 3 is rounded, the lot keeps the rest
+        let sale = ledger.sell(dec!(1), dec!(0.3), dec!(0)).unwrap();
+        assert_eq!(sale.cost_basis, dec!(100.33333333333333333333333333));
+        assert_eq!(ledger.cost_basis(), dec!(200.66666666666666666666666667));
+        assert_eq!(sale.cost_basis + ledger.cost_basis(), dec!(301));
+    }
+
+    #[test]
+    fn test_inexact_totals() {
+        let methods = [
+            CostBasisMethod::Fifo,
+            CostBasisMethod::Lifo,
+            CostBasisMethod::Hifo,
+            CostBasisMethod::AverageCost,
+        ];
+        for method in methods {
+            let mut ledger = Ledger::new(method);
+            ledger.buy(dec!(1e28), dec!(0), dec!(0)).unwrap();
+            // 10^28 + 1.5 would be rounded to 10^28 + 2
+            assert_eq!(
+                ledger.buy(dec!(1.5), dec!(1), dec!(0)),
+                Err(DecUtilsError::Overflow)
+            );
+            assert_eq!(ledger.qty(), dec!(1e28));
+            assert_eq!(
+                ledger.sell(dec!(1e28) + dec!(2), dec!(0), dec!(0)),
+                Err(DecUtilsError::InsufficientLots {
+                    held: dec!(1e28),
+                    qty: dec!(1e28) + dec!(2)
+                })
+            );
+            assert!(ledger.sell(dec!(1e28), dec!(0), dec!(0)).is_ok());
+            assert!(ledger.lots().is_empty());
+            assert!(ledger.sell(dec!(1), dec!(0), dec!(0)).is_err());
+        }
+    }
+}
@@ -1,6 +1,59 @@
+//! Formatting, parsing and arithmetic helpers for `rust_decimal`
+//!
+//! # Errors
+//!
+//! Every `dec_to_*` formatting function has a `try_` twin returning
+//! `Result<String, DecUtilsError>`. The twin fails when the formatter
+//! would produce an ambiguous result, e.g. a currency whose group
+//! separator equals its decimal mark. The `dec_to_*` function never
+//! fails, it falls back to the `Decimal`'s own `Display` output such as
+//! "-1234.567" instead. The built-in presets and locales are always
+//! valid so their twins always return Ok, they exist so callers can
+//! handle every formatter the same way.
+
 use rust_decimal::prelude::*;
-use rusty_money::{iso, Money};
-use separator::Separatable;
+
+mod allocate;
+mod compact;
+pub mod crypto;
+mod currency;
+mod digits;
+mod display;
+mod error;
+mod filters;
+mod formatter;
+pub mod json;
+mod ledger;
+mod locale;
+mod money;
+mod rates;
+mod rounding;
+mod scientific;
+pub mod serde;
+mod table;
+mod words;
+
+pub use allocate::{
+    allocate, allocate_with_strategy, split_evenly, split_evenly_with_strategy, RemainderStrategy,
+};
+pub use compact::{CompactFormatter, CompactScale};
+pub use crypto::{CryptoCurrency, CryptoRegistry};
+pub use currency::Currency;
+pub use display::{Separated, Usd};
+pub use error::DecUtilsError;
+pub use filters::{step_precision, FilterError, SymbolFilters};
+pub use formatter::{Alignment, DecimalFormatter, Formatted, PadPosition, SignStyle, ZeroStyle};
+pub use ledger::{CostBasisMethod, Disposal, Ledger, LedgerEvent, Lot};
+pub use locale::{Locale, NumberSymbols, NBSP, NNBSP};
+pub use money::{Amount, CurrencyCode};
+pub use rates::{ExchangeRate, ExchangeRates};
+pub use rounding::RoundingMode;
+pub use rusty_money::iso;
+pub use scientific::{ExponentStyle, Notation, ScientificFormatter};
+pub use table::{Column, Table, TableStyle};
+pub use words::{
+    AmountWords, Capitalization, English, FractionStyle, Spanish, UnitWords, WordTable,
+};
 
 /// Convert a decimal to string or an empty string if None
 ///
@@ -20,11 +73,12 @@ use separator::Separatable;
 /// assert_eq!(v_str, "");
 /// ```
 pub fn dec_to_string_or_empty(d: Option<Decimal>) -> String {
-    if let Some(q) = d {
-        format!("{}", q)
-    } else {
-        "".to_owned()
-    }
+    DecimalFormatter::new().format_option(d)
+}
+
+/// Fallible twin of `dec_to_string_or_empty`
+pub fn try_dec_to_string_or_empty(d: Option<Decimal>) -> Result<String, DecUtilsError> {
+    DecimalFormatter::new().try_format_option(d)
 }
 
 /// Convert a decimal to a USD string using "Bankers Rounding"
@@ -49,13 +103,41 @@ pub fn dec_to_string_or_empty(d: Option<Decimal>) -> String {
 /// assert_eq!(v_str, "$123.13");
 /// ```
 pub fn dec_to_usd_string(v: Decimal) -> String {
-    let v_string = v.round_dp(2).to_string();
-    let money_string: String = match Money::from_str(&v_string, iso::USD) {
-        Ok(v) => format!("{}", v),
-        Err(e) => format!("({} {})", v_string, e),
-    };
+    DecimalFormatter::usd().format(v)
+}
+
+/// Fallible twin of `dec_to_usd_string`
+pub fn try_dec_to_usd_string(v: Decimal) -> Result<String, DecUtilsError> {
+    DecimalFormatter::usd().try_format(v)
+}
+
+/// Convert a decimal to a USD string using `rounding`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_usd_string_with_rounding, RoundingMode};
+///
+/// let v = dec!(123.125);
+/// let v_str = &dec_to_usd_string_with_rounding(v, RoundingMode::HALF_UP);
+/// assert_eq!(v_str, "$123.13");
+///
+/// let v = dec!(123.129);
+/// let v_str = &dec_to_usd_string_with_rounding(v, RoundingMode::ToZero);
+/// assert_eq!(v_str, "$123.12");
+/// ```
+pub fn dec_to_usd_string_with_rounding(v: Decimal, rounding: RoundingMode) -> String {
+    DecimalFormatter::usd().rounding(rounding).format(v)
+}
 
-    money_string
+/// Fallible twin of `dec_to_usd_string_with_rounding`
+pub fn try_dec_to_usd_string_with_rounding(
+    v: Decimal,
+    rounding: RoundingMode,
+) -> Result<String, DecUtilsError> {
+    DecimalFormatter::usd().rounding(rounding).try_format(v)
 }
 
 /// Convert a a string with comma separators at the 1,000 place
@@ -92,37 +174,573 @@ pub fn dec_to_usd_string(v: Decimal) -> String {
 /// assert_eq!(v_str, "-123,456.13");
 /// ```
 pub fn dec_to_separated_string(v: Decimal, dp: u32) -> String {
-    let negative = v.is_sign_negative();
-    let rounded = v.abs().round_dp(dp);
-    let integral_part = rounded.trunc();
-    let fractional_part = rounded.fract();
-
-    let fractional_part_string = fractional_part.to_string();
-    let fractional_part_str = if dp == 0 {
-        // No fractional part
-        ""
+    DecimalFormatter::separated(dp).format(v)
+}
+
+/// Fallible twin of `dec_to_separated_string`
+pub fn try_dec_to_separated_string(v: Decimal, dp: u32) -> Result<String, DecUtilsError> {
+    DecimalFormatter::separated(dp).try_format(v)
+}
+
+/// Convert a decimal to a string with comma separators at the 1,000
+/// place using `rounding`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_separated_string_with_rounding, RoundingMode};
+///
+/// let v = dec!(-123456.125);
+/// let v_str = &dec_to_separated_string_with_rounding(v, 2, RoundingMode::HALF_UP);
+/// assert_eq!(v_str, "-123,456.13");
+///
+/// let v = dec!(-123456.121);
+/// let v_str = &dec_to_separated_string_with_rounding(v, 2, RoundingMode::FLOOR);
+/// assert_eq!(v_str, "-123,456.13");
+/// ```
+pub fn dec_to_separated_string_with_rounding(
+    v: Decimal,
+    dp: u32,
+    rounding: RoundingMode,
+) -> String {
+    DecimalFormatter::separated(dp).rounding(rounding).format(v)
+}
+
+/// Fallible twin of `dec_to_separated_string_with_rounding`
+pub fn try_dec_to_separated_string_with_rounding(
+    v: Decimal,
+    dp: u32,
+    rounding: RoundingMode,
+) -> Result<String, DecUtilsError> {
+    DecimalFormatter::separated(dp)
+        .rounding(rounding)
+        .try_format(v)
+}
+
+/// Convert a decimal to a string with comma separators at the 1,000
+/// place and exactly `dp` decimal places using "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_fixed_separated_string;
+///
+/// let v = dec!(5);
+/// let v_str = &dec_to_fixed_separated_string(v, 2);
+/// assert_eq!(v_str, "5.00");
+///
+/// let v = dec!(1.1);
+/// let v_str = &dec_to_fixed_separated_string(v, 2);
+/// assert_eq!(v_str, "1.10");
+///
+/// let v = dec!(-123456.126);
+/// let v_str = &dec_to_fixed_separated_string(v, 2);
+/// assert_eq!(v_str, "-123,456.13");
+/// ```
+pub fn dec_to_fixed_separated_string(v: Decimal, dp: u32) -> String {
+    DecimalFormatter::separated(dp).fixed_dp(dp).format(v)
+}
+
+/// Fallible twin of `dec_to_fixed_separated_string`
+pub fn try_dec_to_fixed_separated_string(v: Decimal, dp: u32) -> Result<String, DecUtilsError> {
+    DecimalFormatter::separated(dp).fixed_dp(dp).try_format(v)
+}
+
+/// Convert a decimal to a string with the digit grouping and decimal
+/// mark of `locale` using "Bankers Rounding" to `dp` decimal places
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_locale_string, Locale};
+///
+/// let v = dec!(-1234567.126);
+/// let v_str = &dec_to_locale_string(v, 2, Locale::DeDe);
+/// assert_eq!(v_str, "-1.234.567,13");
+///
+/// let v_str = &dec_to_locale_string(v, 0, Locale::EnIn);
+/// assert_eq!(v_str, "-12,34,567");
+/// ```
+pub fn dec_to_locale_string(v: Decimal, dp: u32, locale: Locale) -> String {
+    DecimalFormatter::new().dp(dp).locale(locale).format(v)
+}
+
+/// Fallible twin of `dec_to_locale_string`
+pub fn try_dec_to_locale_string(
+    v: Decimal,
+    dp: u32,
+    locale: Locale,
+) -> Result<String, DecUtilsError> {
+    DecimalFormatter::new().dp(dp).locale(locale).try_format(v)
+}
+
+/// Convert a decimal to a money string in `currency` using "Bankers
+/// Rounding" to the currency's ISO 4217 minor unit exponent
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_money_string, iso};
+///
+/// let v = dec!(1234.565);
+/// assert_eq!(dec_to_money_string(v, iso::USD), "$1,234.56");
+/// assert_eq!(dec_to_money_string(v, iso::EUR), "€1.234,56");
+/// assert_eq!(dec_to_money_string(v, iso::GBP), "£1,234.56");
+/// assert_eq!(dec_to_money_string(v, iso::JPY), "¥1,235");
+/// assert_eq!(dec_to_money_string(v, iso::KWD), "د.ك 1,234.565");
+/// assert_eq!(dec_to_money_string(-v, iso::KRW), "-₩1,235");
+/// ```
+pub fn dec_to_money_string<C: Currency + ?Sized>(v: Decimal, currency: &C) -> String {
+    DecimalFormatter::money(currency).format(v)
+}
+
+/// Convert a decimal to a money string in `currency` or return an
+/// error if the currency's number symbols are invalid
+pub fn try_dec_to_money_string<C: Currency + ?Sized>(
+    v: Decimal,
+    currency: &C,
+) -> Result<String, DecUtilsError> {
+    DecimalFormatter::money(currency).try_format(v)
+}
+
+/// Convert a decimal to a money string in the ISO 4217 or built-in
+/// crypto currency with `code`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{try_dec_to_money_string_by_code, DecUtilsError};
+///
+/// let v = dec!(1234.5);
+/// assert_eq!(try_dec_to_money_string_by_code(v, "EUR"), Ok("€1.234,50".to_owned()));
+/// assert_eq!(try_dec_to_money_string_by_code(v, "BTC"), Ok("₿1,234.50000000".to_owned()));
+/// assert_eq!(
+///     try_dec_to_money_string_by_code(v, "XYZ"),
+///     Err(DecUtilsError::UnknownCurrency("XYZ".to_owned()))
+/// );
+/// ```
+pub fn try_dec_to_money_string_by_code(v: Decimal, code: &str) -> Result<String, DecUtilsError> {
+    if let Some(currency) = iso::find(code) {
+        try_dec_to_money_string(v, currency)
+    } else if let Some(currency) = crypto::find(code) {
+        try_dec_to_money_string(v, currency)
     } else {
-        // There is at least one value to right of decimal point
-        // and the values are known to be "ascii" which is utf8.
-        // Thus we'll skip the leading "0" and get everything else
-        // as our str.
-        let fractional_part_utf8 = &fractional_part_string.as_bytes()[1..];
-        std::str::from_utf8(fractional_part_utf8).unwrap()
-    };
-    format!(
-        "{}{}{}",
-        if negative {
-            "-".to_owned()
-        } else {
-            "".to_owned()
-        },
-        integral_part.to_u128().unwrap().separated_string(),
-        if fractional_part_str.is_empty() {
-            ""
-        } else {
-            fractional_part_str
-        },
-    )
+        Err(DecUtilsError::UnknownCurrency(code.to_owned()))
+    }
+}
+
+/// Convert a decimal to a money string in `currency` using `rounding`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_money_string_with_rounding, iso, RoundingMode};
+///
+/// let v = dec!(1234.5);
+/// let v_str = &dec_to_money_string_with_rounding(v, iso::JPY, RoundingMode::HALF_UP);
+/// assert_eq!(v_str, "¥1,235");
+/// ```
+pub fn dec_to_money_string_with_rounding<C: Currency + ?Sized>(
+    v: Decimal,
+    currency: &C,
+    rounding: RoundingMode,
+) -> String {
+    DecimalFormatter::money(currency)
+        .rounding(rounding)
+        .format(v)
+}
+
+/// Convert a decimal to a money string in `currency` using `rounding`
+/// or return an error if the currency's number symbols are invalid
+pub fn try_dec_to_money_string_with_rounding<C: Currency + ?Sized>(
+    v: Decimal,
+    currency: &C,
+    rounding: RoundingMode,
+) -> Result<String, DecUtilsError> {
+    DecimalFormatter::money(currency)
+        .rounding(rounding)
+        .try_format(v)
+}
+
+/// Convert a decimal to an accounting string, comma separators at the
+/// 1,000 place and negative values in parentheses, using "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_accounting_string;
+///
+/// let v = dec!(-1234.565);
+/// let v_str = &dec_to_accounting_string(v, 2);
+/// assert_eq!(v_str, "(1,234.56)");
+///
+/// let v = dec!(1234.565);
+/// let v_str = &dec_to_accounting_string(v, 2);
+/// assert_eq!(v_str, "1,234.56");
+/// ```
+pub fn dec_to_accounting_string(v: Decimal, dp: u32) -> String {
+    DecimalFormatter::separated(dp)
+        .sign_style(SignStyle::Parentheses)
+        .format(v)
+}
+
+/// Fallible twin of `dec_to_accounting_string`
+pub fn try_dec_to_accounting_string(v: Decimal, dp: u32) -> Result<String, DecUtilsError> {
+    DecimalFormatter::separated(dp)
+        .sign_style(SignStyle::Parentheses)
+        .try_format(v)
+}
+
+/// Convert a decimal to a USD accounting string, negative values in
+/// parentheses, using "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_usd_accounting_string;
+///
+/// let v = dec!(-1234.565);
+/// let v_str = &dec_to_usd_accounting_string(v);
+/// assert_eq!(v_str, "($1,234.56)");
+/// ```
+pub fn dec_to_usd_accounting_string(v: Decimal) -> String {
+    DecimalFormatter::usd()
+        .sign_style(SignStyle::Parentheses)
+        .format(v)
+}
+
+/// Fallible twin of `dec_to_usd_accounting_string`
+pub fn try_dec_to_usd_accounting_string(v: Decimal) -> Result<String, DecUtilsError> {
+    DecimalFormatter::usd()
+        .sign_style(SignStyle::Parentheses)
+        .try_format(v)
+}
+
+/// Convert a decimal to an abbreviated string with a unit suffix
+/// from `scale`, rounded to `significant_digits` using "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_compact_string, CompactScale};
+///
+/// let v = dec!(1234);
+/// assert_eq!(dec_to_compact_string(v, 2, CompactScale::Short), "1.2K");
+///
+/// let v = dec!(34_512_345);
+/// assert_eq!(dec_to_compact_string(v, 3, CompactScale::Short), "34.5M");
+///
+/// let v = dec!(2_500_000);
+/// assert_eq!(dec_to_compact_string(v, 3, CompactScale::Indian), "25L");
+/// assert_eq!(dec_to_compact_string(v, 3, CompactScale::Si), "2.5M");
+/// ```
+pub fn dec_to_compact_string(v: Decimal, significant_digits: u32, scale: CompactScale) -> String {
+    CompactFormatter::new()
+        .significant_digits(significant_digits)
+        .scale(scale)
+        .format(v)
+}
+
+/// Fallible twin of `dec_to_compact_string`
+pub fn try_dec_to_compact_string(
+    v: Decimal,
+    significant_digits: u32,
+    scale: CompactScale,
+) -> Result<String, DecUtilsError> {
+    CompactFormatter::new()
+        .significant_digits(significant_digits)
+        .scale(scale)
+        .try_format(v)
+}
+
+/// Convert a decimal to an abbreviated USD string with a short scale
+/// unit suffix, rounded to `significant_digits` using "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_compact_usd_string;
+///
+/// let v = dec!(2_149_000_000);
+/// assert_eq!(dec_to_compact_usd_string(v, 2), "$2.1B");
+///
+/// let v = dec!(-12.345);
+/// assert_eq!(dec_to_compact_usd_string(v, 3), "-$12.3");
+/// ```
+pub fn dec_to_compact_usd_string(v: Decimal, significant_digits: u32) -> String {
+    CompactFormatter::new()
+        .significant_digits(significant_digits)
+        .base(DecimalFormatter::usd())
+        .format(v)
+}
+
+/// Fallible twin of `dec_to_compact_usd_string`
+pub fn try_dec_to_compact_usd_string(
+    v: Decimal,
+    significant_digits: u32,
+) -> Result<String, DecUtilsError> {
+    CompactFormatter::new()
+        .significant_digits(significant_digits)
+        .base(DecimalFormatter::usd())
+        .try_format(v)
+}
+
+/// Convert a decimal to a string with comma separators at the 1,000
+/// place rounded to `significant_digits` using "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_significant_string;
+///
+/// let v = dec!(0.00000123);
+/// let v_str = &dec_to_significant_string(v, 4);
+/// assert_eq!(v_str, "0.000001230");
+///
+/// let v = dec!(65000);
+/// let v_str = &dec_to_significant_string(v, 4);
+/// assert_eq!(v_str, "65,000");
+///
+/// let v = dec!(-65123.45);
+/// let v_str = &dec_to_significant_string(v, 4);
+/// assert_eq!(v_str, "-65,120");
+/// ```
+pub fn dec_to_significant_string(v: Decimal, significant_digits: u32) -> String {
+    DecimalFormatter::separated(0)
+        .significant_digits(significant_digits)
+        .format(v)
+}
+
+/// Fallible twin of `dec_to_significant_string`
+pub fn try_dec_to_significant_string(
+    v: Decimal,
+    significant_digits: u32,
+) -> Result<String, DecUtilsError> {
+    DecimalFormatter::separated(0)
+        .significant_digits(significant_digits)
+        .try_format(v)
+}
+
+/// Convert a decimal to scientific notation with `mantissa_digits`
+/// significant digits using "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_scientific_string;
+///
+/// let v = dec!(0.00000012345);
+/// let v_str = &dec_to_scientific_string(v, 5);
+/// assert_eq!(v_str, "1.2345e-7");
+///
+/// let v = dec!(-65000);
+/// let v_str = &dec_to_scientific_string(v, 2);
+/// assert_eq!(v_str, "-6.5e4");
+/// ```
+pub fn dec_to_scientific_string(v: Decimal, mantissa_digits: u32) -> String {
+    ScientificFormatter::new()
+        .mantissa_digits(mantissa_digits)
+        .format(v)
+}
+
+/// Fallible twin of `dec_to_scientific_string`
+pub fn try_dec_to_scientific_string(
+    v: Decimal,
+    mantissa_digits: u32,
+) -> Result<String, DecUtilsError> {
+    Ok(dec_to_scientific_string(v, mantissa_digits))
+}
+
+/// Convert a decimal to engineering notation, where the exponent is a
+/// multiple of 3, with `mantissa_digits` significant digits using
+/// "Bankers Rounding"
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_engineering_string;
+///
+/// let v = dec!(0.00000012345);
+/// let v_str = &dec_to_engineering_string(v, 5);
+/// assert_eq!(v_str, "123.45e-9");
+///
+/// let v = dec!(-65000);
+/// let v_str = &dec_to_engineering_string(v, 2);
+/// assert_eq!(v_str, "-65e3");
+/// ```
+pub fn dec_to_engineering_string(v: Decimal, mantissa_digits: u32) -> String {
+    ScientificFormatter::new()
+        .notation(Notation::Engineering)
+        .mantissa_digits(mantissa_digits)
+        .format(v)
+}
+
+/// Fallible twin of `dec_to_engineering_string`
+pub fn try_dec_to_engineering_string(
+    v: Decimal,
+    mantissa_digits: u32,
+) -> Result<String, DecUtilsError> {
+    Ok(dec_to_engineering_string(v, mantissa_digits))
+}
+
+/// Parse a string produced by `dec_to_string_or_empty`, an empty
+/// string is None
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::parse_string_or_empty;
+///
+/// assert_eq!(parse_string_or_empty("-123.40"), Ok(Some(dec!(-123.40))));
+/// assert_eq!(parse_string_or_empty(""), Ok(None));
+/// assert!(parse_string_or_empty("abc").is_err());
+/// ```
+pub fn parse_string_or_empty(s: &str) -> Result<Option<Decimal>, DecUtilsError> {
+    DecimalFormatter::new().parse_option(s)
+}
+
+/// Parse a string produced by `dec_to_usd_string`, the "$" is optional
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::parse_usd_string;
+///
+/// assert_eq!(parse_usd_string("$1,234.56"), Ok(dec!(1234.56)));
+/// assert_eq!(parse_usd_string("-$0.10"), Ok(dec!(-0.10)));
+/// assert_eq!(parse_usd_string("($1,234.56)"), Ok(dec!(-1234.56)));
+/// assert!(parse_usd_string("$1.234,56").is_err());
+/// ```
+pub fn parse_usd_string(s: &str) -> Result<Decimal, DecUtilsError> {
+    DecimalFormatter::usd().parse(s)
+}
+
+/// Parse a string produced by `dec_to_separated_string`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::parse_separated_string;
+///
+/// assert_eq!(parse_separated_string("-123,456.13"), Ok(dec!(-123456.13)));
+/// assert_eq!(parse_separated_string("1000"), Ok(dec!(1000)));
+/// assert_eq!(parse_separated_string("1,234.56-"), Ok(dec!(-1234.56)));
+/// assert_eq!(parse_separated_string("1,234.56 CR"), Ok(dec!(-1234.56)));
+/// assert!(parse_separated_string("1,000,").is_err());
+/// assert!(parse_separated_string("1,5").is_err());
+/// ```
+pub fn parse_separated_string(s: &str) -> Result<Decimal, DecUtilsError> {
+    DecimalFormatter::separated(0).parse(s)
+}
+
+/// Parse a string produced by `dec_to_locale_string`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{parse_locale_string, Locale};
+///
+/// assert_eq!(parse_locale_string("-1.234.567,13", Locale::DeDe), Ok(dec!(-1234567.13)));
+/// assert_eq!(parse_locale_string("12,34,567", Locale::EnIn), Ok(dec!(1234567)));
+/// ```
+pub fn parse_locale_string(s: &str, locale: Locale) -> Result<Decimal, DecUtilsError> {
+    DecimalFormatter::new().locale(locale).parse(s)
+}
+
+/// Parse a string produced by `dec_to_money_string`, the currency
+/// symbol is optional
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{crypto, iso, parse_money_string};
+///
+/// assert_eq!(parse_money_string("€1.234,50", iso::EUR), Ok(dec!(1234.50)));
+/// assert_eq!(parse_money_string("12,30 kr", iso::SEK), Ok(dec!(12.30)));
+/// assert_eq!(parse_money_string("₿0.00012345", crypto::BTC), Ok(dec!(0.00012345)));
+/// ```
+pub fn parse_money_string<C: Currency + ?Sized>(
+    s: &str,
+    currency: &C,
+) -> Result<Decimal, DecUtilsError> {
+    DecimalFormatter::money(currency).parse(s)
+}
+
+/// Parse a string produced by `dec_to_scientific_string` or
+/// `dec_to_engineering_string`, see `ScientificFormatter::parse`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::parse_scientific_string;
+///
+/// assert_eq!(parse_scientific_string("1.2345e-7"), Ok(dec!(0.00000012345)));
+/// assert_eq!(parse_scientific_string("-65E3"), Ok(dec!(-65000)));
+/// assert!(parse_scientific_string("1e100").is_err());
+/// ```
+pub fn parse_scientific_string(s: &str) -> Result<Decimal, DecUtilsError> {
+    ScientificFormatter::parse(s)
+}
+
+/// Spell out a USD amount for a check using "Bankers Rounding", see
+/// `AmountWords`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::dec_to_usd_words;
+///
+/// let v = dec!(1234.565);
+/// let v_str = &dec_to_usd_words(v);
+/// assert_eq!(v_str, "One thousand two hundred thirty-four and 56/100 dollars");
+/// ```
+pub fn dec_to_usd_words(v: Decimal) -> String {
+    AmountWords::new().format(v)
+}
+
+/// Fallible twin of `dec_to_usd_words`
+pub fn try_dec_to_usd_words(v: Decimal) -> Result<String, DecUtilsError> {
+    Ok(dec_to_usd_words(v))
 }
 
 #[cfg(test)]
@@ -144,6 +762,40 @@ mod tests {
         assert_eq!(dec_to_usd_string(dec!(1000.026)), "$1,000.03");
     }
 
+    #[test]
+    fn test_dec_to_usd_string_with_rounding() {
+        let v = dec!(1000.125);
+        assert_eq!(
+            dec_to_usd_string_with_rounding(v, RoundingMode::default()),
+            dec_to_usd_string(v)
+        );
+        assert_eq!(
+            dec_to_usd_string_with_rounding(v, RoundingMode::HALF_UP),
+            "$1,000.13"
+        );
+        assert_eq!(
+            dec_to_usd_string_with_rounding(dec!(-1.001), RoundingMode::AwayFromZero),
+            "-$1.01"
+        );
+    }
+
+    #[test]
+    fn test_dec_to_separated_string_with_rounding() {
+        let v = dec!(-1000.125);
+        assert_eq!(
+            dec_to_separated_string_with_rounding(v, 2, RoundingMode::default()),
+            dec_to_separated_string(v, 2)
+        );
+        assert_eq!(
+            dec_to_separated_string_with_rounding(v, 2, RoundingMode::HALF_DOWN),
+            "-1,000.12"
+        );
+        assert_eq!(
+            dec_to_separated_string_with_rounding(v, 0, RoundingMode::CEILING),
+            "-1,000"
+        );
+    }
+
     #[test]
     fn test_dec_to_separated_string() {
         assert_eq!(dec_to_separated_string(dec!(0), 0), "0");
@@ -159,5 +811,357 @@ mod tests {
         assert_eq!(dec_to_separated_string(dec!(-999.9), 0), "-1,000");
         assert_eq!(dec_to_separated_string(dec!(1000.026), 2), "1,000.03");
         assert_eq!(dec_to_separated_string(dec!(-1000.026), 2), "-1,000.03");
+        assert_eq!(dec_to_separated_string(dec!(-0.001), 0), "0");
+        assert_eq!(dec_to_separated_string(dec!(-0.001), 2), "0.00");
+        assert_eq!(dec_to_separated_string(dec!(-0.005), 2), "0.00");
+        assert_eq!(dec_to_separated_string(dec!(-0.006), 2), "-0.01");
+    }
+
+    #[test]
+    fn test_dec_to_fixed_separated_string() {
+        assert_eq!(dec_to_fixed_separated_string(dec!(0), 0), "0");
+        assert_eq!(dec_to_fixed_separated_string(dec!(0), 2), "0.00");
+        assert_eq!(dec_to_fixed_separated_string(dec!(1.1), 2), "1.10");
+        assert_eq!(dec_to_fixed_separated_string(dec!(-1.1), 3), "-1.100");
+        assert_eq!(dec_to_fixed_separated_string(dec!(999.999), 2), "1,000.00");
+        assert_eq!(dec_to_fixed_separated_string(dec!(1.125), 2), "1.12");
+    }
+
+    #[test]
+    fn test_dec_to_locale_string() {
+        let v = dec!(1234567.5);
+        assert_eq!(dec_to_locale_string(v, 2, Locale::EnUs), "1,234,567.5");
+        assert_eq!(dec_to_locale_string(v, 2, Locale::ItIt), "1.234.567,5");
+        assert_eq!(
+            dec_to_locale_string(v, 0, Locale::SvSe),
+            "1\u{a0}234\u{a0}568"
+        );
+        assert_eq!(
+            dec_to_locale_string(v, 1, Locale::FrCh),
+            "1\u{202f}234\u{202f}567,5"
+        );
+        assert_eq!(dec_to_locale_string(v, 1, Locale::DeCh), "1'234'567.5");
+    }
+
+    #[test]
+    fn test_dec_to_money_string() {
+        assert_eq!(dec_to_money_string(dec!(1000.026), iso::USD), "$1,000.03");
+        assert_eq!(dec_to_money_string(dec!(1000), iso::EUR), "€1.000,00");
+        assert_eq!(dec_to_money_string(dec!(-0.5), iso::JPY), "¥0");
+        assert_eq!(dec_to_money_string(dec!(1.5), iso::KWD), "د.ك 1.500");
+        assert_eq!(dec_to_money_string(dec!(100000), iso::INR), "₹1,00,000.00");
+        assert_eq!(dec_to_money_string(dec!(12.3), iso::SEK), "12,30 kr");
+    }
+
+    #[test]
+    fn test_dec_to_money_string_with_rounding() {
+        let v = dec!(2.5);
+        assert_eq!(
+            dec_to_money_string_with_rounding(v, iso::KRW, RoundingMode::default()),
+            dec_to_money_string(v, iso::KRW)
+        );
+        assert_eq!(
+            dec_to_money_string_with_rounding(v, iso::KRW, RoundingMode::HALF_UP),
+            "₩3"
+        );
+    }
+
+    #[test]
+    fn test_try_variants() {
+        let v = dec!(-1234.565);
+        assert_eq!(try_dec_to_string_or_empty(None), Ok("".to_owned()));
+        assert_eq!(try_dec_to_usd_string(v), Ok(dec_to_usd_string(v)));
+        assert_eq!(
+            try_dec_to_usd_string_with_rounding(v, RoundingMode::HALF_UP),
+            Ok(dec_to_usd_string_with_rounding(v, RoundingMode::HALF_UP))
+        );
+        assert_eq!(
+            try_dec_to_separated_string(v, 1),
+            Ok(dec_to_separated_string(v, 1))
+        );
+        assert_eq!(
+            try_dec_to_separated_string_with_rounding(v, 2, RoundingMode::FLOOR),
+            Ok(dec_to_separated_string_with_rounding(
+                v,
+                2,
+                RoundingMode::FLOOR
+            ))
+        );
+        assert_eq!(
+            try_dec_to_fixed_separated_string(v, 4),
+            Ok(dec_to_fixed_separated_string(v, 4))
+        );
+        assert_eq!(
+            try_dec_to_locale_string(v, 2, Locale::FrFr),
+            Ok(dec_to_locale_string(v, 2, Locale::FrFr))
+        );
+        assert_eq!(
+            try_dec_to_money_string(v, iso::KWD),
+            Ok(dec_to_money_string(v, iso::KWD))
+        );
+        assert_eq!(
+            try_dec_to_money_string_with_rounding(v, iso::JPY, RoundingMode::ToZero),
+            Ok("-¥1,234".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_try_dec_to_money_string_by_code() {
+        let v = dec!(1);
+        assert_eq!(
+            try_dec_to_money_string_by_code(v, "JPY"),
+            Ok("¥1".to_owned())
+        );
+        assert_eq!(
+            try_dec_to_money_string_by_code(v, "eth"),
+            Ok("Ξ1.000000".to_owned())
+        );
+        assert!(try_dec_to_money_string_by_code(v, "").is_err());
+    }
+
+    #[test]
+    fn test_dec_to_separated_string_edge_cases() {
+        let max = "79,228,162,514,264,337,593,543,950,335";
+        for dp in [0, 1, 2, 28, 29, u32::MAX] {
+            assert_eq!(dec_to_separated_string(Decimal::MAX, dp), max);
+            assert_eq!(
+                dec_to_separated_string(Decimal::MIN, dp),
+                format!("-{}", max)
+            );
+        }
+
+        let max_scale = Decimal::from_i128_with_scale(Decimal::MAX.mantissa(), 28);
+        assert_eq!(
+            dec_to_separated_string(max_scale, 28),
+            "7.9228162514264337593543950335"
+        );
+        assert_eq!(
+            dec_to_separated_string(max_scale, 27),
+            "7.922816251426433759354395034"
+        );
+        assert_eq!(dec_to_separated_string(max_scale, 0), "8");
+        assert_eq!(dec_to_separated_string(-max_scale, 2), "-7.92");
+
+        let tiny = Decimal::new(1, 28);
+        assert_eq!(
+            dec_to_separated_string(tiny, 28),
+            "0.0000000000000000000000000001"
+        );
+        assert_eq!(
+            dec_to_separated_string(tiny, 27),
+            "0.000000000000000000000000000"
+        );
+        assert_eq!(dec_to_separated_string(tiny, 0), "0");
+        assert_eq!(
+            dec_to_separated_string(Decimal::new(5, 28), 27),
+            "0.000000000000000000000000000"
+        );
+        assert_eq!(
+            dec_to_separated_string(Decimal::new(15, 28), 27),
+            "0.000000000000000000000000002"
+        );
+
+        assert_eq!(dec_to_separated_string(Decimal::ZERO, 2), "0");
+        assert_eq!(
+            dec_to_separated_string(Decimal::new(0, 28), 28),
+            "0.0000000000000000000000000000"
+        );
+        assert_eq!(dec_to_separated_string(Decimal::ONE, u32::MAX), "1");
+        assert_eq!(dec_to_separated_string(dec!(0.5), 0), "0");
+        assert_eq!(dec_to_separated_string(dec!(1.5), 0), "2");
+        assert_eq!(dec_to_separated_string(dec!(999999.995), 2), "1,000,000.00");
+
+        for mode in [
+            RoundingMode::MidpointNearestEven,
+            RoundingMode::MidpointNearestOdd,
+            RoundingMode::MidpointAwayFromZero,
+            RoundingMode::MidpointTowardZero,
+            RoundingMode::ToZero,
+            RoundingMode::AwayFromZero,
+            RoundingMode::ToNegativeInfinity,
+            RoundingMode::ToPositiveInfinity,
+        ] {
+            for v in [
+                Decimal::MAX,
+                Decimal::MIN,
+                max_scale,
+                -max_scale,
+                tiny,
+                -tiny,
+            ] {
+                for dp in [0, 14, 28] {
+                    let s = dec_to_separated_string_with_rounding(v, dp, mode);
+                    assert!(!s.is_empty(), "{:?} {} {}", mode, v, dp);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let values = [
+            dec!(0),
+            dec!(-0.004),
+            dec!(1.024),
+            dec!(-1000.026),
+            dec!(123456789.5),
+            Decimal::MAX,
+            Decimal::MIN,
+        ];
+        for v in values {
+            let s = dec_to_string_or_empty(Some(v));
+            assert_eq!(parse_string_or_empty(&s), Ok(Some(v)));
+
+            let s = dec_to_usd_string(v);
+            assert_eq!(parse_usd_string(&s), Ok(v.round_dp(2)));
+
+            for dp in [0, 2, 5] {
+                let s = dec_to_separated_string(v, dp);
+                assert_eq!(parse_separated_string(&s), Ok(v.round_dp(dp)));
+                let s = dec_to_fixed_separated_string(v, dp);
+                assert_eq!(parse_separated_string(&s), Ok(v.round_dp(dp)));
+            }
+
+            for locale in Locale::ALL {
+                let s = dec_to_locale_string(v, 3, locale);
+                assert_eq!(parse_locale_string(&s, locale), Ok(v.round_dp(3)));
+            }
+
+            for currency in [iso::EUR, iso::JPY, iso::KWD, iso::SEK, iso::CHF] {
+                let s = dec_to_money_string(v, currency);
+                let dp = currency.exponent;
+                assert_eq!(parse_money_string(&s, currency), Ok(v.round_dp(dp)));
+            }
+        }
+        assert_eq!(
+            parse_string_or_empty(&dec_to_string_or_empty(None)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_dec_to_accounting_string() {
+        assert_eq!(dec_to_accounting_string(dec!(0), 2), "0");
+        assert_eq!(dec_to_accounting_string(dec!(-0.001), 2), "0.00");
+        assert_eq!(dec_to_accounting_string(dec!(-1000.026), 2), "(1,000.03)");
+        assert_eq!(dec_to_usd_accounting_string(dec!(-1000.026)), "($1,000.03)");
+        assert_eq!(dec_to_usd_accounting_string(dec!(5)), "$5.00");
+        assert_eq!(
+            try_dec_to_accounting_string(dec!(-1), 0),
+            Ok("(1)".to_owned())
+        );
+        assert_eq!(
+            try_dec_to_usd_accounting_string(dec!(-1)),
+            Ok("($1.00)".to_owned())
+        );
+        for v in [dec!(-1234.56), dec!(1234.56)] {
+            assert_eq!(
+                parse_separated_string(&dec_to_accounting_string(v, 2)),
+                Ok(v)
+            );
+            assert_eq!(parse_usd_string(&dec_to_usd_accounting_string(v)), Ok(v));
+        }
+    }
+
+    #[test]
+    fn test_dec_to_compact_string() {
+        let v = dec!(1_234_567);
+        assert_eq!(dec_to_compact_string(v, 3, CompactScale::Short), "1.23M");
+        assert_eq!(dec_to_compact_string(v, 3, CompactScale::Long), "1.23M");
+        assert_eq!(dec_to_compact_string(v, 3, CompactScale::Indian), "12.3L");
+        assert_eq!(dec_to_compact_string(v, 1, CompactScale::Si), "1M");
+        assert_eq!(
+            dec_to_compact_string(dec!(0.0456), 3, CompactScale::Short),
+            "0.0456"
+        );
+        assert_eq!(
+            try_dec_to_compact_string(v, 4, CompactScale::Short),
+            Ok("1.235M".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_dec_to_compact_usd_string() {
+        assert_eq!(dec_to_compact_usd_string(dec!(0), 2), "$0");
+        assert_eq!(dec_to_compact_usd_string(dec!(1_500), 2), "$1.5K");
+        assert_eq!(dec_to_compact_usd_string(dec!(-1_000_000), 3), "-$1M");
+        assert_eq!(
+            try_dec_to_compact_usd_string(dec!(123_456_789_000), 2),
+            Ok("$123B".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_dec_to_significant_string() {
+        assert_eq!(dec_to_significant_string(dec!(0), 4), "0");
+        assert_eq!(
+            dec_to_significant_string(dec!(0.000001234567), 4),
+            "0.000001235"
+        );
+        assert_eq!(dec_to_significant_string(dec!(0.5), 4), "0.5000");
+        assert_eq!(dec_to_significant_string(dec!(12.5), 2), "12");
+        assert_eq!(dec_to_significant_string(dec!(65432.1), 4), "65,430");
+        assert_eq!(dec_to_significant_string(dec!(-999.96), 4), "-1,000");
+        assert_eq!(
+            try_dec_to_significant_string(dec!(1234.5), 6),
+            Ok("1,234.50".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_dec_to_scientific_string() {
+        assert_eq!(dec_to_scientific_string(dec!(0), 3), "0.00e0");
+        assert_eq!(dec_to_scientific_string(dec!(1234.5), 3), "1.23e3");
+        assert_eq!(dec_to_scientific_string(dec!(-0.0009999), 2), "-1.0e-3");
+        assert_eq!(dec_to_scientific_string(Decimal::MAX, 4), "7.923e28");
+    }
+
+    #[test]
+    fn test_dec_to_engineering_string() {
+        assert_eq!(dec_to_engineering_string(dec!(1234.5), 3), "1.23e3");
+        assert_eq!(dec_to_engineering_string(dec!(-0.0123), 3), "-12.3e-3");
+        assert_eq!(dec_to_engineering_string(dec!(123456), 2), "120e3");
+        assert_eq!(dec_to_engineering_string(Decimal::new(1, 28), 1), "100e-30");
+    }
+
+    #[test]
+    fn test_parse_scientific_string() {
+        for v in [dec!(0.00000012345), dec!(-65000), Decimal::MAX] {
+            assert_eq!(
+                parse_scientific_string(&dec_to_scientific_string(v, 29)),
+                Ok(v)
+            );
+            assert_eq!(
+                parse_scientific_string(&dec_to_engineering_string(v, 29)),
+                Ok(v)
+            );
+        }
+        assert!(parse_scientific_string("1.5e").is_err());
+    }
+
+    #[test]
+    fn test_dec_to_usd_words() {
+        assert_eq!(dec_to_usd_words(dec!(0.995)), "One and 00/100 dollars");
+        assert_eq!(
+            dec_to_usd_words(dec!(-12)),
+            "Minus twelve and 00/100 dollars"
+        );
+        assert_eq!(
+            dec_to_usd_words(dec!(1001.10)),
+            "One thousand one and 10/100 dollars"
+        );
+        assert_eq!(
+            try_dec_to_usd_words(dec!(2)),
+            Ok("Two and 00/100 dollars".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_misplaced_separators() {
+        assert!(parse_separated_string("1,5").is_err());
+        assert!(parse_separated_string("12,3456").is_err());
+        assert!(parse_usd_string("$1,2,3.00").is_err());
+        assert!(parse_usd_string("$12,34.00").is_err());
+        assert_eq!(parse_usd_string("$1,234,567.00"), Ok(dec!(1234567.00)));
     }
 }
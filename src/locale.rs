@@ -0,0 +1,428 @@
+use std::fmt;
+
+use crate::DecUtilsError;
+
+/// The symbols and digit grouping used to render a number
+///
+/// Digits left of the decimal mark are grouped from the right, the
+/// first group has `primary_group` digits and all others have
+/// `secondary_group` digits, e.g. 3 and 2 for lakh/crore grouping.
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{DecimalFormatter, NumberSymbols};
+///
+/// let symbols = NumberSymbols::new(Some('\''), '.');
+/// let f = DecimalFormatter::new().symbols(symbols);
+/// assert_eq!(f.format(dec!(1234567.89)), "1'234'567.89");
+///
+/// let symbols = NumberSymbols::new(Some(','), '.').groups(3, 2);
+/// let f = DecimalFormatter::new().symbols(symbols);
+/// assert_eq!(f.format(dec!(1234567.89)), "12,34,567.89");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NumberSymbols {
+    /// The separator between digit groups, None for no grouping
+    pub group_separator: Option<char>,
+
+    /// The character between the integral and fractional parts
+    pub decimal_mark: char,
+
+    /// Number of digits in the group nearest the decimal mark
+    pub primary_group: u8,
+
+    /// Number of digits in all other groups
+    pub secondary_group: u8,
+}
+
+impl Default for NumberSymbols {
+    fn default() -> Self {
+        Self::new(None, '.')
+    }
+}
+
+impl NumberSymbols {
+    /// Symbols with groups of 3 digits
+    pub const fn new(group_separator: Option<char>, decimal_mark: char) -> Self {
+        Self {
+            group_separator,
+            decimal_mark,
+            primary_group: 3,
+            secondary_group: 3,
+        }
+    }
+
+    /// Set the number of digits in the first and subsequent groups
+    pub const fn groups(mut self, primary: u8, secondary: u8) -> Self {
+        self.primary_group = primary;
+        self.secondary_group = secondary;
+        self
+    }
+
+    /// Check that formatted values are unambiguous, the group
+    /// separator and decimal mark must differ and not be digits
+    pub fn validate(&self) -> Result<(), DecUtilsError> {
+        let digit = |c: char| c.is_ascii_digit();
+        if digit(self.decimal_mark)
+            || self.group_separator.is_some_and(digit)
+            || self.group_separator == Some(self.decimal_mark)
+        {
+            return Err(DecUtilsError::InvalidSymbols {
+                group_separator: self.group_separator,
+                decimal_mark: self.decimal_mark,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Convert `s`, digits with optional group separators between
+    /// integral digits and an optional decimal mark, to the plain form
+    /// accepted by `Decimal::from_str`, None if `s` isn't in that form
+    ///
+    /// Grouped digits must be grouped as `write_grouped` groups them, so
+    /// a separator used as a decimal mark, e.g. "1,5", is rejected rather
+    /// than ignored.
+    pub(crate) fn plain_digits(&self, s: &str) -> Option<String> {
+        let (integral, fractional) = match s.split_once(self.decimal_mark) {
+            Some((i, f)) => (i, Some(f)),
+            None => (s, None),
+        };
+
+        let groups: Vec<&str> = match self.group_separator {
+            Some(separator) => integral.split(separator).collect(),
+            None => vec![integral],
+        };
+        if !groups
+            .iter()
+            .all(|g| !g.is_empty() && g.chars().all(|c| c.is_ascii_digit()))
+        {
+            return None;
+        }
+        if let [first, rest @ .., last] = groups.as_slice() {
+            let primary = self.primary_group as usize;
+            let secondary = match self.secondary_group {
+                0 => primary,
+                secondary => secondary as usize,
+            };
+            if primary == 0
+                || last.len() != primary
+                || first.len() > secondary
+                || rest.iter().any(|g| g.len() != secondary)
+            {
+                return None;
+            }
+        }
+
+        let mut plain = String::with_capacity(s.len());
+        plain.extend(groups);
+        if let Some(fractional) = fractional {
+            if fractional.is_empty() || !fractional.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            plain.push('.');
+            plain.push_str(fractional);
+        }
+
+        Some(plain)
+    }
+
+    /// Number of group separators `write_grouped` writes between
+    /// `digits` integral digits
+    pub(crate) fn separator_count(&self, digits: usize) -> usize {
+        match self.group_separator {
+            Some(_) if self.primary_group != 0 && digits > self.primary_group as usize => {
+                let secondary = match self.secondary_group {
+                    0 => self.primary_group,
+                    secondary => secondary,
+                };
+                1 + (digits - self.primary_group as usize - 1) / secondary as usize
+            }
+            _ => 0,
+        }
+    }
+
+    /// Write `digits` to `w` with the group separator between groups
+    pub(crate) fn write_grouped<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        digits: &str,
+    ) -> fmt::Result {
+        let (sep, primary, secondary) = match self.group_separator {
+            Some(sep) if self.primary_group != 0 => {
+                let primary = self.primary_group as usize;
+                let secondary = match self.secondary_group {
+                    0 => primary,
+                    secondary => secondary as usize,
+                };
+                (sep, primary, secondary)
+            }
+            _ => return w.write_str(digits),
+        };
+
+        // Digits are ascii so every index is a char boundary. The last
+        // group is primary digits, those before it are secondary groups
+        // after a possibly shorter first group.
+        let len = digits.len();
+        if len <= primary {
+            return w.write_str(digits);
+        }
+        let head = len - primary;
+        let mut start = match head % secondary {
+            0 => secondary,
+            first => first,
+        };
+        w.write_str(&digits[..start])?;
+        while start < head {
+            w.write_char(sep)?;
+            w.write_str(&digits[start..start + secondary])?;
+            start += secondary;
+        }
+        w.write_char(sep)?;
+        w.write_str(&digits[head..])
+    }
+}
+
+/// No-break space, U+00A0
+pub const NBSP: char = '\u{a0}';
+
+/// Narrow no-break space, U+202F
+pub const NNBSP: char = '\u{202f}';
+
+/// A built-in table of common locales
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{dec_to_locale_string, Locale};
+///
+/// let v = dec!(1234567.891);
+/// assert_eq!(dec_to_locale_string(v, 2, Locale::EnUs), "1,234,567.89");
+/// assert_eq!(dec_to_locale_string(v, 2, Locale::DeDe), "1.234.567,89");
+/// assert_eq!(dec_to_locale_string(v, 2, Locale::FrFr), "1\u{202f}234\u{202f}567,89");
+/// assert_eq!(dec_to_locale_string(v, 2, Locale::DeCh), "1'234'567.89");
+/// assert_eq!(dec_to_locale_string(v, 2, Locale::EnIn), "12,34,567.89");
+/// assert_eq!(Locale::from_tag("de_CH"), Some(Locale::DeCh));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// English (United States), "1,234,567.89"
+    EnUs,
+    /// English (United Kingdom), "1,234,567.89"
+    EnGb,
+    /// English (India), "12,34,567.89"
+    EnIn,
+    /// Hindi (India), "12,34,567.89"
+    HiIn,
+    /// German (Germany), "1.234.567,89"
+    DeDe,
+    /// German (Switzerland), "1'234'567.89"
+    DeCh,
+    /// French (France), "1 234 567,89" with narrow no-break spaces
+    FrFr,
+    /// French (Switzerland), "1 234 567,89" with narrow no-break spaces
+    FrCh,
+    /// Spanish (Spain), "1.234.567,89"
+    EsEs,
+    /// Italian (Italy), "1.234.567,89"
+    ItIt,
+    /// Dutch (Netherlands), "1.234.567,89"
+    NlNl,
+    /// Portuguese (Brazil), "1.234.567,89"
+    PtBr,
+    /// Russian (Russia), "1 234 567,89" with no-break spaces
+    RuRu,
+    /// Swedish (Sweden), "1 234 567,89" with no-break spaces
+    SvSe,
+    /// Polish (Poland), "1 234 567,89" with no-break spaces
+    PlPl,
+    /// Japanese (Japan), "1,234,567.89"
+    JaJp,
+    /// Chinese (China), "1,234,567.89"
+    ZhCn,
+}
+
+impl Locale {
+    /// All of the built-in locales
+    pub const ALL: [Locale; 17] = [
+        Locale::EnUs,
+        Locale::EnGb,
+        Locale::EnIn,
+        Locale::HiIn,
+        Locale::DeDe,
+        Locale::DeCh,
+        Locale::FrFr,
+        Locale::FrCh,
+        Locale::EsEs,
+        Locale::ItIt,
+        Locale::NlNl,
+        Locale::PtBr,
+        Locale::RuRu,
+        Locale::SvSe,
+        Locale::PlPl,
+        Locale::JaJp,
+        Locale::ZhCn,
+    ];
+
+    /// The BCP 47 tag of the locale, e.g. "en-US"
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::EnUs => "en-US",
+            Locale::EnGb => "en-GB",
+            Locale::EnIn => "en-IN",
+            Locale::HiIn => "hi-IN",
+            Locale::DeDe => "de-DE",
+            Locale::DeCh => "de-CH",
+            Locale::FrFr => "fr-FR",
+            Locale::FrCh => "fr-CH",
+            Locale::EsEs => "es-ES",
+            Locale::ItIt => "it-IT",
+            Locale::NlNl => "nl-NL",
+            Locale::PtBr => "pt-BR",
+            Locale::RuRu => "ru-RU",
+            Locale::SvSe => "sv-SE",
+            Locale::PlPl => "pl-PL",
+            Locale::JaJp => "ja-JP",
+            Locale::ZhCn => "zh-CN",
+        }
+    }
+
+    /// Look up a locale by its tag, case insensitive and accepting
+    /// either "-" or "_" as the separator
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let tag = tag.replace('_', "-");
+        Locale::ALL
+            .into_iter()
+            .find(|l| l.tag().eq_ignore_ascii_case(&tag))
+    }
+
+    /// The number symbols of the locale
+    pub fn symbols(self) -> NumberSymbols {
+        match self {
+            Locale::EnUs | Locale::EnGb | Locale::JaJp | Locale::ZhCn => {
+                NumberSymbols::new(Some(','), '.')
+            }
+            Locale::EnIn | Locale::HiIn => NumberSymbols::new(Some(','), '.').groups(3, 2),
+            Locale::DeDe | Locale::EsEs | Locale::ItIt | Locale::NlNl | Locale::PtBr => {
+                NumberSymbols::new(Some('.'), ',')
+            }
+            Locale::DeCh => NumberSymbols::new(Some('\''), '.'),
+            Locale::FrFr | Locale::FrCh => NumberSymbols::new(Some(NNBSP), ','),
+            Locale::RuRu | Locale::SvSe | Locale::PlPl => NumberSymbols::new(Some(NBSP), ','),
+        }
+    }
+}
+
+impl From<Locale> for NumberSymbols {
+    fn from(locale: Locale) -> Self {
+        locale.symbols()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn grouped(symbols: NumberSymbols, digits: &str) -> String {
+        let mut s = String::new();
+        let _ = symbols.write_grouped(&mut s, digits);
+        s
+    }
+
+    #[test]
+    fn test_write_grouped() {
+        let s = NumberSymbols::new(Some(','), '.');
+        assert_eq!(grouped(s, "0"), "0");
+        assert_eq!(grouped(s, "123"), "123");
+        assert_eq!(grouped(s, "1234"), "1,234");
+        assert_eq!(grouped(s, "123456"), "123,456");
+        assert_eq!(grouped(s, "1234567"), "1,234,567");
+        assert_eq!(grouped(NumberSymbols::new(None, '.'), "1234567"), "1234567");
+    }
+
+    #[test]
+    fn test_write_grouped_lakh_crore() {
+        let s = Locale::EnIn.symbols();
+        assert_eq!(grouped(s, "999"), "999");
+        assert_eq!(grouped(s, "1000"), "1,000");
+        assert_eq!(grouped(s, "100000"), "1,00,000");
+        assert_eq!(grouped(s, "10000000"), "1,00,00,000");
+        assert_eq!(grouped(s, "123456789"), "12,34,56,789");
+    }
+
+    #[test]
+    fn test_separator_count() {
+        for symbols in [
+            NumberSymbols::new(Some(','), '.'),
+            NumberSymbols::new(Some(','), '.').groups(3, 2),
+            NumberSymbols::new(Some(','), '.').groups(4, 0),
+            NumberSymbols::new(None, '.'),
+        ] {
+            for len in 1..=29 {
+                let digits = "9".repeat(len);
+                assert_eq!(
+                    symbols.separator_count(len),
+                    grouped(symbols, &digits).len() - len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_plain_digits() {
+        let s = Locale::DeDe.symbols();
+        let p = |v| s.plain_digits(v);
+        assert_eq!(p("1.234.567,89"), Some("1234567.89".to_owned()));
+        assert_eq!(p("1234567"), Some("1234567".to_owned()));
+        assert_eq!(p("0,5"), Some("0.5".to_owned()));
+        assert_eq!(p(""), None);
+        assert_eq!(p(",5"), None);
+        assert_eq!(p("1,"), None);
+        assert_eq!(p(".123"), None);
+        assert_eq!(p("1..234"), None);
+        assert_eq!(p("1.234."), None);
+        assert_eq!(p("1,2,3"), None);
+        assert_eq!(p("1,2.3"), None);
+        assert_eq!(p("1a"), None);
+        // Groups must have the sizes `write_grouped` writes
+        assert_eq!(p("12.345.678"), Some("12345678".to_owned()));
+        assert_eq!(p("1.5"), None);
+        assert_eq!(p("12.3456"), None);
+        assert_eq!(p("1234.567"), None);
+        assert_eq!(p("1.23.456"), None);
+
+        let s = Locale::EnIn.symbols();
+        let p = |v| s.plain_digits(v);
+        assert_eq!(p("12,34,567.5"), Some("1234567.5".to_owned()));
+        assert_eq!(p("1,234"), Some("1234".to_owned()));
+        assert_eq!(p("1,000,000"), None);
+        assert_eq!(p("123,456"), None);
+
+        let s = NumberSymbols::new(Some(','), '.').groups(0, 0);
+        assert_eq!(s.plain_digits("1,234"), None);
+        assert_eq!(s.plain_digits("1234"), Some("1234".to_owned()));
+    }
+
+    #[test]
+    fn test_validate() {
+        for l in Locale::ALL {
+            assert_eq!(l.symbols().validate(), Ok(()));
+        }
+        assert!(NumberSymbols::new(Some(','), ',').validate().is_err());
+        assert!(NumberSymbols::new(Some('1'), '.').validate().is_err());
+        assert!(NumberSymbols::new(None, '0').validate().is_err());
+    }
+
+    #[test]
+    fn test_from_tag() {
+        for l in Locale::ALL {
+            assert_eq!(Locale::from_tag(l.tag()), Some(l));
+        }
+        assert_eq!(Locale::from_tag("EN_us"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_tag("xx-XX"), None);
+    }
+}
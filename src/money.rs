@@ -0,0 +1,324 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use rust_decimal::prelude::*;
+use rusty_money::iso;
+
+use crate::{crypto, Currency, DecUtilsError, DecimalFormatter};
+
+/// A currency code such as "USD" or "BTC", always uppercase
+///
+/// # Example
+/// ```
+/// use dec_utils::{crypto, iso, CurrencyCode};
+///
+/// assert_eq!(CurrencyCode::new("usd"), CurrencyCode::of(iso::USD));
+/// assert_eq!(CurrencyCode::of(crypto::BTC).as_str(), "BTC");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CurrencyCode(String);
+
+impl CurrencyCode {
+    /// The code `code` in uppercase
+    pub fn new(code: &str) -> Self {
+        Self(code.to_ascii_uppercase())
+    }
+
+    /// The code of `currency`
+    pub fn of<C: Currency + ?Sized>(currency: &C) -> Self {
+        Self::new(currency.code())
+    }
+
+    /// The code, e.g. "USD"
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Number of decimal places of the smallest unit of the ISO 4217 or
+    /// built-in crypto currency with this code, e.g. 2 for USD and 8 for
+    /// BTC, None if there is no such currency
+    pub fn minor_units(&self) -> Option<u32> {
+        if let Some(currency) = iso::find(self.as_str()) {
+            Some(currency.exponent())
+        } else {
+            crypto::find(self.as_str()).map(|c| c.native_precision())
+        }
+    }
+
+    /// The money formatter of the ISO 4217 or built-in crypto currency
+    /// with this code, None if there is no such currency
+    pub fn formatter(&self) -> Option<DecimalFormatter> {
+        if let Some(currency) = iso::find(self.as_str()) {
+            Some(DecimalFormatter::money(currency))
+        } else {
+            crypto::find(self.as_str()).map(DecimalFormatter::money)
+        }
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for CurrencyCode {
+    fn from(code: &str) -> Self {
+        Self::new(code)
+    }
+}
+
+impl From<&iso::Currency> for CurrencyCode {
+    fn from(currency: &iso::Currency) -> Self {
+        Self::of(currency)
+    }
+}
+
+impl From<&crypto::CryptoCurrency> for CurrencyCode {
+    fn from(currency: &crypto::CryptoCurrency) -> Self {
+        Self::of(currency)
+    }
+}
+
+/// An amount of money, a decimal value in a currency
+///
+/// Arithmetic between amounts is checked, combining amounts in
+/// different currencies returns `DecUtilsError::CurrencyMismatch`
+/// instead of a meaningless number. Amounts in different currencies are
+/// never equal and have no order.
+///
+/// `Display` uses the money formatter of the ISO 4217 or built-in crypto
+/// currency with the code, see `dec_to_money_string`. Other codes are
+/// shown after the unrounded value, e.g. "12.5 XYZ".
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{crypto, iso, Amount, DecUtilsError};
+///
+/// let price = Amount::new(dec!(1234.5), iso::USD);
+/// let fee = Amount::new(dec!(0.25), iso::USD);
+/// let total = price.checked_add(&fee).unwrap().checked_mul(dec!(2)).unwrap();
+/// assert_eq!(total.to_string(), "$2,469.50");
+/// assert!(fee < price);
+///
+/// let btc = Amount::new(dec!(0.5), crypto::BTC);
+/// assert_eq!(
+///     price.checked_add(&btc),
+///     Err(DecUtilsError::CurrencyMismatch {
+///         left: "USD".to_owned(),
+///         right: "BTC".to_owned()
+///     })
+/// );
+/// assert_eq!(price.partial_cmp(&btc), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Amount {
+    /// The unrounded value, e.g. 1234.5
+    pub value: Decimal,
+    /// The currency the value is in
+    pub currency: CurrencyCode,
+}
+
+impl Amount {
+    /// `value` in `currency`
+    pub fn new(value: Decimal, currency: impl Into<CurrencyCode>) -> Self {
+        Self {
+            value,
+            currency: currency.into(),
+        }
+    }
+
+    /// Zero in `currency`
+    pub fn zero(currency: impl Into<CurrencyCode>) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    /// The sum of `amounts`, all in `currency`
+    ///
+    /// Returns `DecUtilsError::CurrencyMismatch` if an amount is in
+    /// another currency and `DecUtilsError::Overflow` if the sum is too
+    /// large.
+    pub fn try_sum<'a, I>(
+        currency: impl Into<CurrencyCode>,
+        amounts: I,
+    ) -> Result<Self, DecUtilsError>
+    where
+        I: IntoIterator<Item = &'a Amount>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Self::zero(currency), |sum, v| sum.checked_add(v))
+    }
+
+    /// `self + other`
+    ///
+    /// Returns `DecUtilsError::CurrencyMismatch` if the currencies
+    /// differ and `DecUtilsError::Overflow` if the sum is too large.
+    pub fn checked_add(&self, other: &Amount) -> Result<Self, DecUtilsError> {
+        self.same_currency(other)?;
+        self.with_value(self.value.checked_add(other.value))
+    }
+
+    /// `self - other`
+    ///
+    /// Returns `DecUtilsError::CurrencyMismatch` if the currencies
+    /// differ and `DecUtilsError::Overflow` if the difference is too
+    /// large.
+    pub fn checked_sub(&self, other: &Amount) -> Result<Self, DecUtilsError> {
+        self.same_currency(other)?;
+        self.with_value(self.value.checked_sub(other.value))
+    }
+
+    /// `self * n`, e.g. a unit price times a quantity
+    ///
+    /// Returns `DecUtilsError::Overflow` if the product is too large.
+    pub fn checked_mul(&self, n: Decimal) -> Result<Self, DecUtilsError> {
+        self.with_value(self.value.checked_mul(n))
+    }
+
+    /// `self / n`, unrounded
+    ///
+    /// Returns `DecUtilsError::DivisionByZero` if `n` is zero and
+    /// `DecUtilsError::Overflow` if the quotient is too large.
+    pub fn checked_div(&self, n: Decimal) -> Result<Self, DecUtilsError> {
+        if n.is_zero() {
+            return Err(DecUtilsError::DivisionByZero);
+        }
+        self.with_value(self.value.checked_div(n))
+    }
+
+    /// Compare with an amount in the same currency
+    ///
+    /// Returns `DecUtilsError::CurrencyMismatch` if the currencies
+    /// differ.
+    pub fn try_cmp(&self, other: &Amount) -> Result<Ordering, DecUtilsError> {
+        self.same_currency(other)?;
+        Ok(self.value.cmp(&other.value))
+    }
+
+    fn same_currency(&self, other: &Amount) -> Result<(), DecUtilsError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(DecUtilsError::CurrencyMismatch {
+                left: self.currency.to_string(),
+                right: other.currency.to_string(),
+            })
+        }
+    }
+
+    fn with_value(&self, value: Option<Decimal>) -> Result<Self, DecUtilsError> {
+        Ok(Self {
+            value: value.ok_or(DecUtilsError::Overflow)?,
+            currency: self.currency.clone(),
+        })
+    }
+}
+
+impl PartialOrd for Amount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.try_cmp(other).ok()
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.currency.formatter() {
+            Some(formatter) => formatter.write_to(self.value, f),
+            None => write!(f, "{} {}", self.value, self.currency),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_currency_code() {
+        assert_eq!(CurrencyCode::new("eur").as_str(), "EUR");
+        assert_eq!(CurrencyCode::from("Usdt"), CurrencyCode::of(crypto::USDT));
+        assert!(CurrencyCode::new("jpy").formatter().is_some());
+        assert!(CurrencyCode::new("eth").formatter().is_some());
+        assert!(CurrencyCode::new("XYZ").formatter().is_none());
+        assert_eq!(CurrencyCode::new("KWD").minor_units(), Some(3));
+        assert_eq!(CurrencyCode::new("ETH").minor_units(), Some(18));
+        assert_eq!(CurrencyCode::new("XYZ").minor_units(), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Amount::new(dec!(10.50), iso::USD);
+        let b = Amount::new(dec!(0.25), "usd");
+        assert_eq!(a.checked_add(&b), Ok(Amount::new(dec!(10.75), iso::USD)));
+        assert_eq!(a.checked_sub(&b), Ok(Amount::new(dec!(10.25), iso::USD)));
+        assert_eq!(
+            a.checked_mul(dec!(-2)),
+            Ok(Amount::new(dec!(-21), iso::USD))
+        );
+        assert_eq!(
+            a.checked_div(dec!(3)).map(|v| v.value.round_dp(4)),
+            Ok(dec!(3.5))
+        );
+        assert_eq!(a.checked_div(dec!(0)), Err(DecUtilsError::DivisionByZero));
+
+        let max = Amount::new(Decimal::MAX, iso::USD);
+        assert_eq!(max.checked_add(&a), Err(DecUtilsError::Overflow));
+        assert_eq!(max.checked_mul(dec!(2)), Err(DecUtilsError::Overflow));
+        assert_eq!(max.checked_div(dec!(0.1)), Err(DecUtilsError::Overflow));
+
+        let eur = Amount::new(dec!(1), iso::EUR);
+        let mismatch = Err(DecUtilsError::CurrencyMismatch {
+            left: "USD".to_owned(),
+            right: "EUR".to_owned(),
+        });
+        assert_eq!(a.checked_add(&eur), mismatch);
+        assert_eq!(a.checked_sub(&eur), mismatch);
+    }
+
+    #[test]
+    fn test_try_sum() {
+        let amounts = [
+            Amount::new(dec!(1.10), iso::USD),
+            Amount::new(dec!(2.20), iso::USD),
+        ];
+        assert_eq!(
+            Amount::try_sum(iso::USD, &amounts),
+            Ok(Amount::new(dec!(3.30), iso::USD))
+        );
+        assert_eq!(Amount::try_sum(iso::USD, []), Ok(Amount::zero(iso::USD)));
+        assert!(Amount::try_sum(iso::EUR, &amounts).is_err());
+    }
+
+    #[test]
+    fn test_cmp() {
+        let a = Amount::new(dec!(1.0), iso::USD);
+        let b = Amount::new(dec!(1.00), iso::USD);
+        let c = Amount::new(dec!(2), iso::USD);
+        let d = Amount::new(dec!(1), crypto::BTC);
+        assert_eq!(a, b);
+        assert!(a < c);
+        assert_eq!(a.try_cmp(&c), Ok(Ordering::Less));
+        assert_ne!(a, d);
+        assert_eq!(a.partial_cmp(&d), None);
+        assert!(a.try_cmp(&d).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Amount::new(dec!(-1234.5), iso::EUR).to_string(),
+            "-€1.234,50"
+        );
+        assert_eq!(Amount::new(dec!(1.005), iso::JPY).to_string(), "¥1");
+        assert_eq!(
+            Amount::new(dec!(0.123456789), crypto::BTC).to_string(),
+            "₿0.12345679"
+        );
+        assert_eq!(Amount::new(dec!(12.50), "xyz").to_string(), "12.50 XYZ");
+    }
+}
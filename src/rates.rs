@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use rust_decimal::prelude::*;
+
+use crate::{Amount, CurrencyCode, DecUtilsError, RoundingMode};
+
+/// The price of one unit of a currency in another and when it was quoted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExchangeRate {
+    /// The price of one unit of the first currency in the second
+    pub rate: Decimal,
+    /// When the rate was quoted
+    pub timestamp: SystemTime,
+}
+
+/// A table of exchange rates between currency pairs
+///
+/// A conversion uses the rate of the pair if there is one, else the
+/// inverse of the opposite pair, else it's triangulated through the base
+/// currency, e.g. BTC to EUR as BTC to USD then USD to EUR with a USD
+/// base. The timestamp of a triangulated rate is that of its older leg.
+///
+/// # Example
+/// ```
+/// use std::time::SystemTime;
+///
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{crypto, iso, Amount, ExchangeRates, RoundingMode};
+///
+/// let now = SystemTime::now();
+/// let mut rates = ExchangeRates::new(iso::USD);
+/// rates.insert(crypto::BTC, iso::USD, dec!(64250.10), now).unwrap();
+/// rates.insert(iso::EUR, iso::USD, dec!(1.08), now).unwrap();
+///
+/// let balance = Amount::new(dec!(0.5), crypto::BTC);
+/// let usd = rates.convert(&balance, iso::USD, RoundingMode::default()).unwrap();
+/// assert_eq!(usd.to_string(), "$32,125.05");
+/// let eur = rates.convert(&balance, iso::EUR, RoundingMode::default()).unwrap();
+/// assert_eq!(eur.to_string(), "€29.745,42");
+/// let btc = rates.convert(&usd, crypto::BTC, RoundingMode::default()).unwrap();
+/// assert_eq!(btc.to_string(), "₿0.50000000");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExchangeRates {
+    base: CurrencyCode,
+    rates: HashMap<(CurrencyCode, CurrencyCode), ExchangeRate>,
+}
+
+impl ExchangeRates {
+    /// An empty table triangulating through `base`
+    pub fn new(base: impl Into<CurrencyCode>) -> Self {
+        Self {
+            base: base.into(),
+            rates: HashMap::new(),
+        }
+    }
+
+    /// The currency conversions are triangulated through
+    pub fn base(&self) -> &CurrencyCode {
+        &self.base
+    }
+
+    /// Set the price of one `from` in `to` as of `timestamp`, returning
+    /// the rate it replaces
+    ///
+    /// Returns `DecUtilsError::InvalidRate` if `rate` isn't positive.
+    pub fn insert(
+        &mut self,
+        from: impl Into<CurrencyCode>,
+        to: impl Into<CurrencyCode>,
+        rate: Decimal,
+        timestamp: SystemTime,
+    ) -> Result<Option<ExchangeRate>, DecUtilsError> {
+        if rate.is_sign_negative() || rate.is_zero() {
+            return Err(DecUtilsError::InvalidRate(rate));
+        }
+        let rate = ExchangeRate { rate, timestamp };
+        Ok(self.rates.insert((from.into(), to.into()), rate))
+    }
+
+    /// Remove the rate of a pair, its inverse is kept
+    pub fn remove(
+        &mut self,
+        from: impl Into<CurrencyCode>,
+        to: impl Into<CurrencyCode>,
+    ) -> Option<ExchangeRate> {
+        self.rates.remove(&(from.into(), to.into()))
+    }
+
+    /// The price of one `from` in `to`
+    ///
+    /// A rate of the pair is returned as inserted, inverse and
+    /// triangulated rates are rounded to the 28 significant digits of a
+    /// `Decimal`. A currency is worth exactly 1 of itself, with a
+    /// timestamp of `SystemTime::UNIX_EPOCH`. Returns
+    /// `DecUtilsError::MissingRate` if there is no way to convert and
+    /// `DecUtilsError::Overflow` if an inverse or triangulated rate is
+    /// too large.
+    pub fn rate(
+        &self,
+        from: impl Into<CurrencyCode>,
+        to: impl Into<CurrencyCode>,
+    ) -> Result<ExchangeRate, DecUtilsError> {
+        let (from, to) = (from.into(), to.into());
+        if from == to {
+            return Ok(ExchangeRate {
+                rate: Decimal::ONE,
+                timestamp: SystemTime::UNIX_EPOCH,
+            });
+        }
+        if let Some(rate) = self.direct_or_inverse(&from, &to)? {
+            return Ok(rate);
+        }
+        let missing = || DecUtilsError::MissingRate {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        if from == self.base || to == self.base {
+            return Err(missing());
+        }
+        let first = self
+            .direct_or_inverse(&from, &self.base)?
+            .ok_or_else(missing)?;
+        let second = self
+            .direct_or_inverse(&self.base, &to)?
+            .ok_or_else(missing)?;
+        Ok(ExchangeRate {
+            rate: first
+                .rate
+                .checked_mul(second.rate)
+                .ok_or(DecUtilsError::Overflow)?,
+            timestamp: first.timestamp.min(second.timestamp),
+        })
+    }
+
+    /// Convert `amount` to `to`, rounded to the minor units of `to`
+    /// using `rounding`, the native precision for a crypto currency
+    ///
+    /// Returns `DecUtilsError::UnknownCurrency` if `to` isn't an ISO 4217
+    /// or built-in crypto currency, or the errors of `rate`.
+    pub fn convert(
+        &self,
+        amount: &Amount,
+        to: impl Into<CurrencyCode>,
+        rounding: RoundingMode,
+    ) -> Result<Amount, DecUtilsError> {
+        let to = to.into();
+        let dp = to
+            .minor_units()
+            .ok_or_else(|| DecUtilsError::UnknownCurrency(to.to_string()))?;
+        let rate = self.rate(amount.currency.clone(), to.clone())?;
+        let value = amount
+            .value
+            .checked_mul(rate.rate)
+            .ok_or(DecUtilsError::Overflow)?;
+        Ok(Amount::new(rounding.round_dp(value, dp), to))
+    }
+
+    fn direct_or_inverse(
+        &self,
+        from: &CurrencyCode,
+        to: &CurrencyCode,
+    ) -> Result<Option<ExchangeRate>, DecUtilsError> {
+        if let Some(rate) = self.rates.get(&(from.clone(), to.clone())) {
+            return Ok(Some(*rate));
+        }
+        self.rates
+            .get(&(to.clone(), from.clone()))
+            .map(|inverse| {
+                Ok(ExchangeRate {
+                    rate: Decimal::ONE
+                        .checked_div(inverse.rate)
+                        .ok_or(DecUtilsError::Overflow)?,
+                    timestamp: inverse.timestamp,
+                })
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{crypto, iso};
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+
+    fn rates() -> ExchangeRates {
+        let t = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let mut rates = ExchangeRates::new(iso::USD);
+        rates
+            .insert(crypto::BTC, iso::USD, dec!(60000), t(300))
+            .unwrap();
+        rates.insert(iso::USD, iso::JPY, dec!(150), t(200)).unwrap();
+        rates
+            .insert(iso::GBP, iso::USD, dec!(1.25), t(100))
+            .unwrap();
+        rates
+    }
+
+    #[test]
+    fn test_rate() {
+        let rates = rates();
+        let t = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(
+            rates.rate(crypto::BTC, iso::USD),
+            Ok(ExchangeRate {
+                rate: dec!(60000),
+                timestamp: t(300)
+            })
+        );
+        // Inverse
+        assert_eq!(
+            rates.rate(iso::USD, iso::GBP).map(|r| r.rate),
+            Ok(dec!(0.8))
+        );
+        // Triangulated, with the older timestamp
+        assert_eq!(
+            rates.rate(iso::GBP, iso::JPY),
+            Ok(ExchangeRate {
+                rate: dec!(187.5),
+                timestamp: t(100)
+            })
+        );
+        assert_eq!(rates.rate("btc", "gbp").map(|r| r.rate), Ok(dec!(48000)));
+        assert_eq!(rates.rate(iso::EUR, iso::EUR).map(|r| r.rate), Ok(dec!(1)));
+        assert_eq!(
+            rates.rate(iso::EUR, iso::USD),
+            Err(DecUtilsError::MissingRate {
+                from: "EUR".to_owned(),
+                to: "USD".to_owned()
+            })
+        );
+        assert!(rates.rate(iso::JPY, iso::EUR).is_err());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut rates = rates();
+        let now = SystemTime::now();
+        assert_eq!(
+            rates.insert(iso::EUR, iso::USD, dec!(0), now),
+            Err(DecUtilsError::InvalidRate(dec!(0)))
+        );
+        assert!(rates.insert(iso::EUR, iso::USD, dec!(-1), now).is_err());
+        let previous = rates.insert(crypto::BTC, iso::USD, dec!(61000), now);
+        assert_eq!(previous.unwrap().map(|r| r.rate), Some(dec!(60000)));
+        assert_eq!(
+            rates.rate(crypto::BTC, iso::USD),
+            Ok(ExchangeRate {
+                rate: dec!(61000),
+                timestamp: now
+            })
+        );
+        // Inverse rates are rounded to 28 significant digits
+        rates.insert("ABC", iso::USD, dec!(3), now).unwrap();
+        assert_eq!(
+            rates.rate(iso::USD, "ABC").map(|r| r.rate),
+            Ok(dec!(0.3333333333333333333333333333))
+        );
+        rates
+            .insert("XYZ", iso::USD, Decimal::new(1, 28), now)
+            .unwrap();
+        assert_eq!(rates.rate(iso::USD, "XYZ").map(|r| r.rate), Ok(dec!(1e28)));
+        assert!(rates.remove(iso::JPY, iso::USD).is_none());
+        assert!(rates.remove(iso::USD, iso::JPY).is_some());
+        assert!(rates.rate(iso::JPY, iso::USD).is_err());
+    }
+
+    #[test]
+    fn test_convert() {
+        let rates = rates();
+        let convert = |v, from: &str, to: &str, rounding| {
+            rates
+                .convert(&Amount::new(v, from), to, rounding)
+                .map(|a| a.to_string())
+        };
+        let even = RoundingMode::default();
+        assert_eq!(
+            convert(dec!(0.12345678), "BTC", "USD", even),
+            Ok("$7,407.41".to_owned())
+        );
+        assert_eq!(
+            convert(dec!(100), "GBP", "JPY", even),
+            Ok("¥18,750".to_owned())
+        );
+        assert_eq!(
+            convert(dec!(1), "JPY", "USD", RoundingMode::CEILING),
+            Ok("$0.01".to_owned())
+        );
+        assert_eq!(
+            convert(dec!(1), "JPY", "USD", RoundingMode::FLOOR),
+            Ok("$0.00".to_owned())
+        );
+        // Native precision, 8 decimal places for BTC
+        assert_eq!(
+            rates
+                .convert(&Amount::new(dec!(1), iso::USD), crypto::BTC, even)
+                .map(|a| a.value),
+            Ok(dec!(0.00001667))
+        );
+        assert_eq!(
+            convert(dec!(1), "USD", "XYZ", even),
+            Err(DecUtilsError::UnknownCurrency("XYZ".to_owned()))
+        );
+        assert_eq!(
+            convert(Decimal::MAX, "BTC", "USD", even),
+            Err(DecUtilsError::Overflow)
+        );
+    }
+}
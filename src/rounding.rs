@@ -0,0 +1,276 @@
+use rust_decimal::prelude::*;
+
+/// The rounding applied when a value is reduced to a number of decimal places
+///
+/// The variants mirror rust_decimal's `RoundingStrategy` plus
+/// `MidpointNearestOdd`. The default is `MidpointNearestEven`,
+/// "Bankers Rounding", which is what `dec_to_usd_string` and
+/// `dec_to_separated_string` use.
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::RoundingMode;
+///
+/// assert_eq!(RoundingMode::default().round_dp(dec!(2.5), 0), dec!(2));
+/// assert_eq!(RoundingMode::HALF_UP.round_dp(dec!(2.5), 0), dec!(3));
+/// assert_eq!(RoundingMode::HALF_DOWN.round_dp(dec!(2.5), 0), dec!(2));
+/// assert_eq!(RoundingMode::HALF_ODD.round_dp(dec!(2.5), 0), dec!(3));
+/// assert_eq!(RoundingMode::CEILING.round_dp(dec!(-2.5), 0), dec!(-2));
+/// assert_eq!(RoundingMode::FLOOR.round_dp(dec!(-2.5), 0), dec!(-3));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Midpoints round to the nearest even number, "Bankers Rounding",
+    /// e.g. 6.5 -> 6, 7.5 -> 8
+    #[default]
+    MidpointNearestEven,
+
+    /// Midpoints round to the nearest odd number, e.g. 6.5 -> 7, 7.5 -> 7
+    MidpointNearestOdd,
+
+    /// Midpoints round away from zero, e.g. 6.5 -> 7, -6.5 -> -7
+    MidpointAwayFromZero,
+
+    /// Midpoints round toward zero, e.g. 6.5 -> 6, -6.5 -> -6
+    MidpointTowardZero,
+
+    /// Always round toward zero, e.g. 6.8 -> 6, -6.8 -> -6
+    ToZero,
+
+    /// Always round away from zero, e.g. 6.2 -> 7, -6.2 -> -7
+    AwayFromZero,
+
+    /// Always round toward negative infinity, e.g. 6.8 -> 6, -6.2 -> -7
+    ToNegativeInfinity,
+
+    /// Always round toward positive infinity, e.g. 6.2 -> 7, -6.8 -> -6
+    ToPositiveInfinity,
+}
+
+impl RoundingMode {
+    /// "Bankers Rounding"
+    pub const HALF_EVEN: RoundingMode = RoundingMode::MidpointNearestEven;
+
+    /// Half-odd rounding
+    pub const HALF_ODD: RoundingMode = RoundingMode::MidpointNearestOdd;
+
+    /// Half-up rounding, as commonly required on invoices
+    pub const HALF_UP: RoundingMode = RoundingMode::MidpointAwayFromZero;
+
+    /// Half-down rounding
+    pub const HALF_DOWN: RoundingMode = RoundingMode::MidpointTowardZero;
+
+    /// Round toward positive infinity
+    pub const CEILING: RoundingMode = RoundingMode::ToPositiveInfinity;
+
+    /// Round toward negative infinity
+    pub const FLOOR: RoundingMode = RoundingMode::ToNegativeInfinity;
+
+    /// Round `v` to `dp` decimal places
+    pub fn round_dp(self, v: Decimal, dp: u32) -> Decimal {
+        let strategy = match self {
+            RoundingMode::MidpointNearestEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::MidpointNearestOdd => return round_dp_nearest_odd(v, dp),
+            RoundingMode::MidpointAwayFromZero => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::MidpointTowardZero => RoundingStrategy::MidpointTowardZero,
+            RoundingMode::ToZero => RoundingStrategy::ToZero,
+            RoundingMode::AwayFromZero => RoundingStrategy::AwayFromZero,
+            RoundingMode::ToNegativeInfinity => RoundingStrategy::ToNegativeInfinity,
+            RoundingMode::ToPositiveInfinity => RoundingStrategy::ToPositiveInfinity,
+        };
+
+        v.round_dp_with_strategy(dp, strategy)
+    }
+
+    /// Round `v` to `significant_digits` significant digits, which may
+    /// round integral digits, e.g. 65,432 to 4 is 65,430. Zero
+    /// significant digits is treated as 1.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal::prelude::*;
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::RoundingMode;
+    ///
+    /// let m = RoundingMode::default();
+    /// assert_eq!(m.round_sf(dec!(0.0000012345), 4), dec!(0.000001234));
+    /// assert_eq!(m.round_sf(dec!(65432.1), 4), dec!(65430));
+    /// assert_eq!(m.round_sf(dec!(-99.96), 3), dec!(-100));
+    /// ```
+    pub fn round_sf(self, v: Decimal, significant_digits: u32) -> Decimal {
+        let Some(msd) = msd_exponent(v) else {
+            return v;
+        };
+        let dp = significant_digits.max(1) as i32 - 1 - msd;
+        if dp >= 0 {
+            let rounded = self.round_dp(v, dp as u32);
+
+            // Rounding up to a new most significant digit, e.g. 99.96 to
+            // 100.0, leaves one digit too many which is always a zero.
+            return match msd_exponent(rounded) {
+                Some(r) if r > msd => self.round_sf(rounded, significant_digits),
+                _ => rounded,
+            };
+        }
+
+        // Round integral digits by rounding v / 10^n to 0 places, if
+        // multiplying back overflows then rounding toward zero can't.
+        let n = dp.unsigned_abs();
+        let Some(pow) = pow10(n) else {
+            return Decimal::ZERO;
+        };
+        let scaled = v / pow;
+        self.round_dp(scaled, 0)
+            .checked_mul(pow)
+            .or_else(|| RoundingMode::ToZero.round_dp(scaled, 0).checked_mul(pow))
+            .unwrap_or(v)
+    }
+}
+
+/// The exponent of the most significant digit of `v`, e.g. 2 for 123.4
+/// and -3 for 0.00123, None for zero
+pub(crate) fn msd_exponent(v: Decimal) -> Option<i32> {
+    let mut m = v.mantissa().unsigned_abs();
+    if m == 0 {
+        return None;
+    }
+    let mut digits = 0;
+    while m != 0 {
+        m /= 10;
+        digits += 1;
+    }
+    Some(digits - 1 - v.scale() as i32)
+}
+
+/// 10^n, None if it doesn't fit in a Decimal
+fn pow10(n: u32) -> Option<Decimal> {
+    10i128
+        .checked_pow(n)
+        .and_then(|p| Decimal::try_from_i128_with_scale(p, 0).ok())
+}
+
+impl From<RoundingStrategy> for RoundingMode {
+    #[allow(deprecated)]
+    fn from(strategy: RoundingStrategy) -> Self {
+        match strategy {
+            RoundingStrategy::MidpointNearestEven | RoundingStrategy::BankersRounding => {
+                RoundingMode::MidpointNearestEven
+            }
+            RoundingStrategy::MidpointAwayFromZero | RoundingStrategy::RoundHalfUp => {
+                RoundingMode::MidpointAwayFromZero
+            }
+            RoundingStrategy::MidpointTowardZero | RoundingStrategy::RoundHalfDown => {
+                RoundingMode::MidpointTowardZero
+            }
+            RoundingStrategy::ToZero | RoundingStrategy::RoundDown => RoundingMode::ToZero,
+            RoundingStrategy::AwayFromZero | RoundingStrategy::RoundUp => {
+                RoundingMode::AwayFromZero
+            }
+            RoundingStrategy::ToNegativeInfinity => RoundingMode::ToNegativeInfinity,
+            RoundingStrategy::ToPositiveInfinity => RoundingMode::ToPositiveInfinity,
+        }
+    }
+}
+
+fn round_dp_nearest_odd(v: Decimal, dp: u32) -> Decimal {
+    let down = v.round_dp_with_strategy(dp, RoundingStrategy::ToZero);
+    let up = v.round_dp_with_strategy(dp, RoundingStrategy::AwayFromZero);
+    if down == up || (v - down).abs() != (up - v).abs() {
+        // Not a midpoint so all the "Midpoint" strategies agree
+        return v.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero);
+    }
+
+    // The rounded down value has exactly dp decimal places, so the
+    // last digit of its mantissa is the one that must be odd.
+    if down.mantissa() % 2 != 0 {
+        down
+    } else {
+        up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_round_dp() {
+        let cases = [
+            (dec!(6.5), [6, 7, 7, 6, 6, 7, 6, 7]),
+            (dec!(7.5), [8, 7, 8, 7, 7, 8, 7, 8]),
+            (dec!(-6.5), [-6, -7, -7, -6, -6, -7, -7, -6]),
+            (dec!(6.8), [7, 7, 7, 7, 6, 7, 6, 7]),
+            (dec!(-6.2), [-6, -6, -6, -6, -6, -7, -7, -6]),
+        ];
+        let modes = [
+            RoundingMode::MidpointNearestEven,
+            RoundingMode::MidpointNearestOdd,
+            RoundingMode::MidpointAwayFromZero,
+            RoundingMode::MidpointTowardZero,
+            RoundingMode::ToZero,
+            RoundingMode::AwayFromZero,
+            RoundingMode::ToNegativeInfinity,
+            RoundingMode::ToPositiveInfinity,
+        ];
+        for (v, expected) in cases {
+            for (mode, e) in modes.iter().zip(expected) {
+                assert_eq!(mode.round_dp(v, 0), Decimal::from(e), "{:?} {}", mode, v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_sf() {
+        let m = RoundingMode::default();
+        assert_eq!(m.round_sf(dec!(0), 3), dec!(0));
+        assert_eq!(m.round_sf(dec!(0.00000123), 4), dec!(0.00000123));
+        assert_eq!(m.round_sf(dec!(65000), 4), dec!(65000));
+        assert_eq!(m.round_sf(dec!(65015), 4), dec!(65020));
+        assert_eq!(m.round_sf(dec!(65025), 4), dec!(65020));
+        assert_eq!(m.round_sf(dec!(123.456), 0), dec!(100));
+        assert_eq!(m.round_sf(dec!(-0.0456), 1), dec!(-0.05));
+        assert_eq!(m.round_sf(Decimal::new(1, 28), 3), Decimal::new(1, 28));
+        assert_eq!(
+            m.round_sf(Decimal::MAX, 2),
+            dec!(79_000_000_000_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            m.round_sf(Decimal::MAX, 4),
+            dec!(79_220_000_000_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            RoundingMode::AwayFromZero.round_sf(Decimal::MIN, 1),
+            dec!(-70_000_000_000_000_000_000_000_000_000)
+        );
+        assert_eq!(RoundingMode::FLOOR.round_sf(dec!(-1234), 2), dec!(-1300));
+        assert_eq!(m.round_sf(dec!(99.96), 3).scale(), 0);
+        assert_eq!(m.round_sf(dec!(0.9996), 3).to_string(), "1.00");
+    }
+
+    #[test]
+    fn test_msd_exponent() {
+        assert_eq!(msd_exponent(dec!(0)), None);
+        assert_eq!(msd_exponent(dec!(0.000)), None);
+        assert_eq!(msd_exponent(dec!(1)), Some(0));
+        assert_eq!(msd_exponent(dec!(-123.4)), Some(2));
+        assert_eq!(msd_exponent(dec!(0.00123)), Some(-3));
+        assert_eq!(msd_exponent(Decimal::MAX), Some(28));
+        assert_eq!(msd_exponent(Decimal::new(1, 28)), Some(-28));
+    }
+
+    #[test]
+    fn test_round_dp_nearest_odd() {
+        let m = RoundingMode::MidpointNearestOdd;
+        assert_eq!(m.round_dp(dec!(1.225), 2), dec!(1.23));
+        assert_eq!(m.round_dp(dec!(1.235), 2), dec!(1.23));
+        assert_eq!(m.round_dp(dec!(-1.245), 2), dec!(-1.25));
+        assert_eq!(m.round_dp(dec!(1.2451), 2), dec!(1.25));
+        assert_eq!(m.round_dp(dec!(1.2), 2), dec!(1.2));
+    }
+}
@@ -0,0 +1,383 @@
+use std::fmt::Write;
+
+use rust_decimal::prelude::*;
+
+use crate::rounding::msd_exponent;
+use crate::{DecUtilsError, RoundingMode};
+
+/// Where the exponent of scientific notation may fall
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// One integral digit, e.g. "1.2345e-7"
+    #[default]
+    Scientific,
+
+    /// The exponent is a multiple of 3 with 1 to 3 integral
+    /// digits, e.g. "123.45e-9"
+    Engineering,
+}
+
+/// How the exponent is written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ExponentStyle {
+    /// "1.5e-7"
+    #[default]
+    LowerE,
+
+    /// "1.5E-7"
+    UpperE,
+
+    /// "1.5×10^-7"
+    TimesTen,
+}
+
+impl ExponentStyle {
+    fn marker(self) -> &'static str {
+        match self {
+            ExponentStyle::LowerE => "e",
+            ExponentStyle::UpperE => "E",
+            ExponentStyle::TimesTen => "×10^",
+        }
+    }
+}
+
+/// Formats decimals in scientific or engineering notation
+///
+/// The value is rounded to `mantissa_digits` significant digits and all
+/// of them are shown, so trailing zeros are kept.
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{ExponentStyle, Notation, RoundingMode, ScientificFormatter};
+///
+/// let f = ScientificFormatter::new().mantissa_digits(5);
+/// assert_eq!(f.format(dec!(0.00000012345)), "1.2345e-7");
+/// assert_eq!(f.format(dec!(-65000)), "-6.5000e4");
+///
+/// let f = f.notation(Notation::Engineering);
+/// assert_eq!(f.format(dec!(0.00000012345)), "123.45e-9");
+///
+/// let f = ScientificFormatter::new()
+///     .mantissa_digits(2)
+///     .rounding(RoundingMode::CEILING)
+///     .exponent_style(ExponentStyle::TimesTen);
+/// assert_eq!(f.format(dec!(1234)), "1.3×10^3");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScientificFormatter {
+    notation: Notation,
+    mantissa_digits: u32,
+    exponent_style: ExponentStyle,
+    rounding: RoundingMode,
+}
+
+impl Default for ScientificFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScientificFormatter {
+    /// A scientific notation formatter with 6 mantissa digits,
+    /// an "e" exponent and "Bankers Rounding"
+    pub fn new() -> Self {
+        Self {
+            notation: Notation::Scientific,
+            mantissa_digits: 6,
+            exponent_style: ExponentStyle::LowerE,
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    /// Scientific or engineering notation
+    pub fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    /// Number of significant digits in the mantissa, 1 to 29, the most
+    /// a `Decimal` holds
+    pub fn mantissa_digits(mut self, mantissa_digits: u32) -> Self {
+        self.mantissa_digits = mantissa_digits.clamp(1, 29);
+        self
+    }
+
+    /// How the exponent is written
+    pub fn exponent_style(mut self, exponent_style: ExponentStyle) -> Self {
+        self.exponent_style = exponent_style;
+        self
+    }
+
+    /// The mode used when rounding to the mantissa digits
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Format `v`
+    pub fn format(&self, v: Decimal) -> String {
+        let sf = self.mantissa_digits as usize;
+        let Some(mut msd) = msd_exponent(v) else {
+            let zeros = "0".repeat(sf - 1);
+            let mark = if zeros.is_empty() { "" } else { "." };
+            return format!("0{}{}{}0", mark, zeros, self.exponent_style.marker());
+        };
+
+        // Round the value with its point moved after the first digit,
+        // that scale is always valid so nothing can overflow, e.g.
+        // Decimal::MAX rounds up to "7.923e28".
+        let mantissa = v.mantissa();
+        let scale = (v.scale() as i32 + msd) as u32;
+        let normalized = Decimal::from_i128_with_scale(mantissa, scale);
+        let mut rounded = self.rounding.round_dp(normalized, self.mantissa_digits - 1);
+        if rounded.abs() >= Decimal::TEN {
+            msd += 1;
+            rounded /= Decimal::TEN;
+        }
+        let exponent = match self.notation {
+            Notation::Scientific => msd,
+            Notation::Engineering => msd.div_euclid(3) * 3,
+        };
+        let integral_len = (msd - exponent) as usize + 1;
+
+        // The rounded mantissa has at most sf significant digits, pad it
+        // to show all of them and all of the integral digits.
+        let mut digits = rounded.mantissa().unsigned_abs().to_string();
+        digits.truncate(sf);
+        let len = sf.max(integral_len);
+        digits.extend(std::iter::repeat_n('0', len - digits.len()));
+
+        let mut s = String::with_capacity(len + 8);
+        if rounded.is_sign_negative() {
+            s.push('-');
+        }
+        s.push_str(&digits[..integral_len]);
+        if len > integral_len {
+            s.push('.');
+            s.push_str(&digits[integral_len..]);
+        }
+        s.push_str(self.exponent_style.marker());
+        let _ = write!(s, "{}", exponent);
+
+        s
+    }
+
+    /// Parse a number in scientific or engineering notation with any
+    /// `ExponentStyle`, or without an exponent
+    ///
+    /// The result is exact, a value too large for a `Decimal` is an
+    /// `OutOfRange` error and one with more decimal places than it can
+    /// hold an `ExcessiveScale` error.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_decimal_macros::dec;
+    ///
+    /// use dec_utils::ScientificFormatter;
+    ///
+    /// assert_eq!(ScientificFormatter::parse("1.2345e-7"), Ok(dec!(0.00000012345)));
+    /// assert_eq!(ScientificFormatter::parse("-123.45E-9"), Ok(dec!(-0.00000012345)));
+    /// assert_eq!(ScientificFormatter::parse("6.5×10^4"), Ok(dec!(65000)));
+    /// assert!(ScientificFormatter::parse("1e29").is_err());
+    /// assert!(ScientificFormatter::parse("1e-29").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Decimal, DecUtilsError> {
+        let invalid = || DecUtilsError::InvalidNumber(s.to_owned());
+
+        let trimmed = s.trim();
+        let (mantissa, exponent) = [
+            ExponentStyle::TimesTen,
+            ExponentStyle::LowerE,
+            ExponentStyle::UpperE,
+        ]
+        .iter()
+        .find_map(|style| trimmed.split_once(style.marker()))
+        .unwrap_or((trimmed, "0"));
+        let exponent: i64 = exponent.trim().parse().map_err(|_| invalid())?;
+        let mantissa = mantissa.trim();
+        let (negative, unsigned) = match mantissa.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+        };
+        let (integral, fractional) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let all_digits = |d: &str| d.chars().all(|c| c.is_ascii_digit());
+        if integral.is_empty() || !all_digits(integral) || !all_digits(fractional) {
+            return Err(invalid());
+        }
+        if unsigned.ends_with('.') {
+            return Err(invalid());
+        }
+
+        // value = digits * 10^(exponent - fractional.len()). Trailing
+        // zeros are kept, so is the scale, unless they don't fit.
+        let digits = format!("{}{}", integral, fractional);
+        let significant = digits.trim_start_matches('0');
+        let trimmed_digits = significant.trim_end_matches('0');
+        let exponent = exponent.saturating_sub(fractional.len() as i64);
+        if trimmed_digits.is_empty() {
+            let scale = if exponent < 0 {
+                exponent.unsigned_abs().min(28) as u32
+            } else {
+                0
+            };
+            return Ok(Decimal::new(0, scale));
+        }
+        let trimmed_exponent =
+            exponent.saturating_add((significant.len() - trimmed_digits.len()) as i64);
+
+        let v = match exact(significant, exponent) {
+            Some(v) => Some(v),
+            None => exact(trimmed_digits, trimmed_exponent),
+        };
+        let v = match v {
+            Some(v) => v,
+            None => {
+                // Too large if the integral part doesn't fit, otherwise
+                // there are too many digits after the decimal mark
+                let len = trimmed_digits.len() as i64;
+                let integral_len = len.saturating_add(trimmed_exponent);
+                let fits = integral_len <= 0
+                    || (integral_len <= 29
+                        && exact(
+                            &trimmed_digits[..integral_len.min(len) as usize],
+                            (integral_len - len).max(0),
+                        )
+                        .is_some());
+                return Err(if fits {
+                    DecUtilsError::ExcessiveScale(s.to_owned())
+                } else {
+                    DecUtilsError::OutOfRange(s.to_owned())
+                });
+            }
+        };
+
+        Ok(if negative { -v } else { v })
+    }
+}
+
+/// `digits` * 10^`exponent` if it is exactly representable
+fn exact(digits: &str, exponent: i64) -> Option<Decimal> {
+    let m: i128 = digits.parse().ok()?;
+    if exponent <= 0 {
+        let scale = u32::try_from(exponent.unsigned_abs()).ok()?;
+        Decimal::try_from_i128_with_scale(m, scale).ok()
+    } else {
+        let pow = 10i128.checked_pow(u32::try_from(exponent).ok()?)?;
+        Decimal::try_from_i128_with_scale(m, 0)
+            .ok()?
+            .checked_mul(Decimal::try_from_i128_with_scale(pow, 0).ok()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_scientific() {
+        let f = ScientificFormatter::new().mantissa_digits(3);
+        assert_eq!(f.format(dec!(0)), "0.00e0");
+        assert_eq!(f.format(dec!(1)), "1.00e0");
+        assert_eq!(f.format(dec!(-0.5)), "-5.00e-1");
+        assert_eq!(f.format(dec!(999.6)), "1.00e3");
+        assert_eq!(f.format(Decimal::MAX), "7.92e28");
+        assert_eq!(f.format(Decimal::MIN), "-7.92e28");
+        assert_eq!(f.format(Decimal::new(1, 28)), "1.00e-28");
+        let f = f.mantissa_digits(1);
+        assert_eq!(f.format(dec!(0)), "0e0");
+        assert_eq!(f.format(dec!(250)), "2e2");
+        let f = f.mantissa_digits(0).exponent_style(ExponentStyle::UpperE);
+        assert_eq!(f.format(dec!(350)), "4E2");
+        let f = f.mantissa_digits(u32::MAX);
+        assert_eq!(f.format(dec!(0)), format!("0.{}E0", "0".repeat(28)));
+        assert_eq!(f.format(dec!(1.5)), format!("1.5{}E0", "0".repeat(27)));
+    }
+
+    #[test]
+    fn test_engineering() {
+        let f = ScientificFormatter::new()
+            .notation(Notation::Engineering)
+            .mantissa_digits(2);
+        assert_eq!(f.format(dec!(1)), "1.0e0");
+        assert_eq!(f.format(dec!(12)), "12e0");
+        assert_eq!(f.format(dec!(123)), "120e0");
+        assert_eq!(f.format(dec!(1234)), "1.2e3");
+        assert_eq!(f.format(dec!(-0.0123)), "-12e-3");
+        assert_eq!(f.format(dec!(0.00123)), "1.2e-3");
+        assert_eq!(f.format(dec!(0.000123)), "120e-6");
+        assert_eq!(f.format(dec!(999.9)), "1.0e3");
+        assert_eq!(f.format(Decimal::new(1, 28)), "100e-30");
+    }
+
+    #[test]
+    fn test_parse() {
+        let p = ScientificFormatter::parse;
+        assert_eq!(p("0"), Ok(dec!(0)));
+        assert_eq!(p("0.00e5"), Ok(dec!(0)));
+        assert_eq!(p("1.5"), Ok(dec!(1.5)));
+        assert_eq!(p(" +1.5e+2 "), Ok(dec!(150)));
+        assert_eq!(p("7.9228162514264337593543950335e28"), Ok(Decimal::MAX));
+        assert_eq!(p("-7.9228162514264337593543950335E28"), Ok(Decimal::MIN));
+        assert_eq!(p("1000.000e-31"), Ok(Decimal::new(1, 28)));
+        assert_eq!(p("100e-30"), Ok(Decimal::new(1, 28)));
+        assert_eq!(p("1.50").map(|v| v.scale()), Ok(2));
+        assert_eq!(p("1.500e1").map(|v| v.scale()), Ok(2));
+        assert_eq!(p("-0.00").map(|v| v.scale()), Ok(2));
+        assert_eq!(p("0e-99").map(|v| v.scale()), Ok(28));
+        assert_eq!(p("79228162514264337593543950335.00"), Ok(Decimal::MAX));
+        let out_of_range = |s: &str| Err(DecUtilsError::OutOfRange(s.to_owned()));
+        let excessive_scale = |s: &str| Err(DecUtilsError::ExcessiveScale(s.to_owned()));
+        let s = "7.9228162514264337593543950336e28";
+        assert_eq!(p(s), out_of_range(s));
+        assert_eq!(p("-1e29"), out_of_range("-1e29"));
+        assert_eq!(p("1e100"), out_of_range("1e100"));
+        assert_eq!(p("1.23e-28"), excessive_scale("1.23e-28"));
+        assert_eq!(p("1e-100"), excessive_scale("1e-100"));
+        let s = "7922816251426433759354395033.51";
+        assert_eq!(p(s), excessive_scale(s));
+        let s = "79228162514264337593543950336.5";
+        assert_eq!(p(s), out_of_range(s));
+        let s = "1.00000000000000000000000000001";
+        assert_eq!(p(s), excessive_scale(s));
+        assert!(p("").is_err());
+        assert!(p("e5").is_err());
+        assert!(p("1.e5").is_err());
+        assert!(p(".5e5").is_err());
+        assert!(p("1e").is_err());
+        assert!(p("1e5e5").is_err());
+        assert!(p("1x5").is_err());
+        assert!(p("--1").is_err());
+        assert!(p("1e99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values = [
+            dec!(0.00000012345),
+            dec!(-65000),
+            dec!(1.5),
+            Decimal::MAX,
+            Decimal::MIN,
+            Decimal::new(1, 28),
+        ];
+        for notation in [Notation::Scientific, Notation::Engineering] {
+            for style in [
+                ExponentStyle::LowerE,
+                ExponentStyle::UpperE,
+                ExponentStyle::TimesTen,
+            ] {
+                let f = ScientificFormatter::new()
+                    .mantissa_digits(29)
+                    .notation(notation)
+                    .exponent_style(style);
+                for v in values {
+                    assert_eq!(ScientificFormatter::parse(&f.format(v)), Ok(v));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,532 @@
+//! Modules for `#[serde(with = "...")]` on `Decimal` fields
+//!
+//! - `string`: a plain string such as "-1234.50"
+//! - `empty_as_none`: an `Option<Decimal>` as a plain string, "" is None
+//! - `usd`: a USD string such as "-$1,234.50"
+//! - `locale::*`: a string with a locale's separators, e.g. "1.234,5"
+//!   for `locale::de_de`, keeping every decimal place
+//! - `lenient`: a plain string but accepting JSON numbers and strings
+//!   in any of the above forms or scientific notation
+//!
+//! Strings are parsed exactly, a value with more digits than a
+//! `Decimal` can hold is an error.
+//!
+//! # Example
+//! ```
+//! use rust_decimal::prelude::*;
+//! use rust_decimal_macros::dec;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Fill {
+//!     #[serde(with = "dec_utils::serde::lenient")]
+//!     qty: Decimal,
+//!     #[serde(with = "dec_utils::serde::usd")]
+//!     price: Decimal,
+//!     #[serde(with = "dec_utils::serde::empty_as_none", default)]
+//!     fee: Option<Decimal>,
+//! }
+//!
+//! let fill: Fill = serde_json::from_str(r#"{"qty": 1.5, "price": "$1,234.50", "fee": ""}"#).unwrap();
+//! assert_eq!(
+//!     fill,
+//!     Fill {
+//!         qty: dec!(1.5),
+//!         price: dec!(1234.50),
+//!         fee: None
+//!     }
+//! );
+//! assert_eq!(
+//!     serde_json::to_string(&fill).unwrap(),
+//!     r#"{"qty":"1.5","price":"$1,234.50","fee":""}"#
+//! );
+//! ```
+use std::fmt;
+
+use ::serde::de::{self, Visitor};
+use rust_decimal::prelude::*;
+
+use crate::DecUtilsError;
+
+/// Deserializes a string with `parse`
+struct ParseVisitor<F> {
+    parse: F,
+    expecting: &'static str,
+}
+
+impl<'de, F> Visitor<'de> for ParseVisitor<F>
+where
+    F: Fn(&str) -> Result<Decimal, DecUtilsError>,
+{
+    type Value = Decimal;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Decimal, E> {
+        (self.parse)(s).map_err(E::custom)
+    }
+}
+
+/// A string such as "-1234.50", like `Decimal`'s `Display`
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Price {
+///     #[serde(with = "dec_utils::serde::string")]
+///     v: Decimal,
+/// }
+///
+/// let p: Price = serde_json::from_str(r#"{"v": "-1234.50"}"#).unwrap();
+/// assert_eq!(p.v, dec!(-1234.50));
+/// assert_eq!(serde_json::to_string(&p).unwrap(), r#"{"v":"-1234.50"}"#);
+/// ```
+pub mod string {
+    use ::serde::{Deserializer, Serializer};
+    use rust_decimal::prelude::*;
+
+    use super::ParseVisitor;
+    use crate::DecimalFormatter;
+
+    /// Serialize `v` as a string
+    pub fn serialize<S: Serializer>(v: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(v)
+    }
+
+    /// Deserialize a decimal from a string
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_str(ParseVisitor {
+            parse: |s: &str| DecimalFormatter::new().parse(s),
+            expecting: "a decimal string",
+        })
+    }
+}
+
+/// An `Option<Decimal>` as a string such as "-1234.50" with None as "",
+/// the serde counterpart of `dec_to_string_or_empty`
+///
+/// A null is also None. Add `#[serde(default)]` for a missing field to
+/// be None too.
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Fee {
+///     #[serde(with = "dec_utils::serde::empty_as_none", default)]
+///     v: Option<Decimal>,
+/// }
+///
+/// let f: Fee = serde_json::from_str(r#"{"v": ""}"#).unwrap();
+/// assert_eq!(f.v, None);
+/// let f: Fee = serde_json::from_str(r#"{}"#).unwrap();
+/// assert_eq!(f.v, None);
+/// let f: Fee = serde_json::from_str(r#"{"v": "0.25"}"#).unwrap();
+/// assert_eq!(f.v, Some(dec!(0.25)));
+/// assert_eq!(serde_json::to_string(&Fee { v: None }).unwrap(), r#"{"v":""}"#);
+/// ```
+pub mod empty_as_none {
+    use std::fmt;
+
+    use ::serde::de::{self, Deserializer, Visitor};
+    use ::serde::Serializer;
+    use rust_decimal::prelude::*;
+
+    use crate::parse_string_or_empty;
+
+    struct EmptyAsNoneVisitor;
+
+    impl<'de> Visitor<'de> for EmptyAsNoneVisitor {
+        type Value = Option<Decimal>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal string, an empty string or null")
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Option<Decimal>, E> {
+            parse_string_or_empty(s).map_err(E::custom)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Option<Decimal>, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Option<Decimal>, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Option<Decimal>, D::Error> {
+            deserializer.deserialize_str(self)
+        }
+    }
+
+    /// Serialize `v` as a string, "" if None
+    pub fn serialize<S: Serializer>(v: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error> {
+        match v {
+            Some(v) => serializer.collect_str(v),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    /// Deserialize an optional decimal from a string, "" or null is None
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Decimal>, D::Error> {
+        deserializer.deserialize_option(EmptyAsNoneVisitor)
+    }
+}
+
+/// A USD string such as "-$1,234.50", see `dec_to_usd_string`
+///
+/// Serializing rounds to 2 decimal places. Deserializing accepts
+/// anything `parse_usd_string` does, the "$" is optional.
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Balance {
+///     #[serde(with = "dec_utils::serde::usd")]
+///     v: Decimal,
+/// }
+///
+/// let b: Balance = serde_json::from_str(r#"{"v": "($1,234.50)"}"#).unwrap();
+/// assert_eq!(b.v, dec!(-1234.50));
+/// assert_eq!(serde_json::to_string(&b).unwrap(), r#"{"v":"-$1,234.50"}"#);
+/// ```
+pub mod usd {
+    use ::serde::{Deserializer, Serializer};
+    use rust_decimal::prelude::*;
+
+    use super::ParseVisitor;
+    use crate::{parse_usd_string, Usd};
+
+    /// Serialize `v` as a USD string
+    pub fn serialize<S: Serializer>(v: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&Usd(v))
+    }
+
+    /// Deserialize a decimal from a USD string
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_str(ParseVisitor {
+            parse: parse_usd_string,
+            expecting: "a USD string",
+        })
+    }
+}
+
+/// A string with the separators of a `Locale`, one module per locale
+///
+/// Serializing keeps every decimal place of the value. Deserializing
+/// accepts anything `parse_locale_string` does.
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Betrag {
+///     #[serde(with = "dec_utils::serde::locale::de_de")]
+///     v: Decimal,
+/// }
+///
+/// let b: Betrag = serde_json::from_str(r#"{"v": "-1.234.567,125"}"#).unwrap();
+/// assert_eq!(b.v, dec!(-1234567.125));
+/// assert_eq!(serde_json::to_string(&b).unwrap(), r#"{"v":"-1.234.567,125"}"#);
+/// ```
+pub mod locale {
+    macro_rules! locale_module {
+        ($name:ident, $locale:ident, $tag:literal) => {
+            #[doc = concat!("A string with the separators of ", $tag)]
+            pub mod $name {
+                use ::serde::{Deserializer, Serializer};
+                use rust_decimal::prelude::*;
+
+                use crate::serde::ParseVisitor;
+                use crate::{parse_locale_string, DecimalFormatter, Locale};
+
+                /// Serialize `v` with the locale's separators
+                pub fn serialize<S: Serializer>(
+                    v: &Decimal,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    let f = DecimalFormatter::new().locale(Locale::$locale);
+                    serializer.collect_str(&f.display(*v))
+                }
+
+                /// Deserialize a decimal with the locale's separators
+                pub fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Decimal, D::Error> {
+                    deserializer.deserialize_str(ParseVisitor {
+                        parse: |s: &str| parse_locale_string(s, Locale::$locale),
+                        expecting: concat!("a decimal string with ", $tag, " separators"),
+                    })
+                }
+            }
+        };
+    }
+
+    locale_module!(en_us, EnUs, "en-US");
+    locale_module!(en_gb, EnGb, "en-GB");
+    locale_module!(en_in, EnIn, "en-IN");
+    locale_module!(hi_in, HiIn, "hi-IN");
+    locale_module!(de_de, DeDe, "de-DE");
+    locale_module!(de_ch, DeCh, "de-CH");
+    locale_module!(fr_fr, FrFr, "fr-FR");
+    locale_module!(fr_ch, FrCh, "fr-CH");
+    locale_module!(es_es, EsEs, "es-ES");
+    locale_module!(it_it, ItIt, "it-IT");
+    locale_module!(nl_nl, NlNl, "nl-NL");
+    locale_module!(pt_br, PtBr, "pt-BR");
+    locale_module!(ru_ru, RuRu, "ru-RU");
+    locale_module!(sv_se, SvSe, "sv-SE");
+    locale_module!(pl_pl, PlPl, "pl-PL");
+    locale_module!(ja_jp, JaJp, "ja-JP");
+    locale_module!(zh_cn, ZhCn, "zh-CN");
+}
+
+/// Serialized as a plain string like `string`, deserialized from a JSON
+/// number or a string that is plain, scientific, USD or "," separated
+///
+/// JSON numbers are exact as the crate enables serde_json's
+/// arbitrary precision.
+///
+/// # Example
+/// ```
+/// use rust_decimal::prelude::*;
+/// use rust_decimal_macros::dec;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Qty {
+///     #[serde(with = "dec_utils::serde::lenient")]
+///     v: Decimal,
+/// }
+///
+/// for (json, v) in [
+///     (r#"{"v": 0.1}"#, dec!(0.1)),
+///     (r#"{"v": 12345678901234567890.123456789}"#, dec!(12345678901234567890.123456789)),
+///     (r#"{"v": "1.5e-7"}"#, dec!(0.00000015)),
+///     (r#"{"v": " $1,234.50 "}"#, dec!(1234.50)),
+///     (r#"{"v": -7}"#, dec!(-7)),
+/// ] {
+///     let q: Qty = serde_json::from_str(json).unwrap();
+///     assert_eq!(q.v, v);
+/// }
+/// assert!(serde_json::from_str::<Qty>(r#"{"v": "abc"}"#).is_err());
+/// ```
+pub mod lenient {
+    use std::fmt;
+
+    use ::serde::de::value::MapAccessDeserializer;
+    use ::serde::de::{self, Deserialize, Deserializer, MapAccess, Unexpected, Visitor};
+    use ::serde::Serializer;
+    use rust_decimal::prelude::*;
+
+    use crate::{DecUtilsError, DecimalFormatter, ScientificFormatter};
+
+    struct LenientVisitor;
+
+    impl<'de> Visitor<'de> for LenientVisitor {
+        type Value = Decimal;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal number or string")
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Decimal, E> {
+            parse(s).map_err(E::custom)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+            Ok(Decimal::from(v))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+            Ok(Decimal::from(v))
+        }
+
+        fn visit_i128<E: de::Error>(self, v: i128) -> Result<Decimal, E> {
+            Decimal::from_i128(v).ok_or_else(|| E::custom(DecUtilsError::Overflow))
+        }
+
+        fn visit_u128<E: de::Error>(self, v: u128) -> Result<Decimal, E> {
+            Decimal::from_u128(v).ok_or_else(|| E::custom(DecUtilsError::Overflow))
+        }
+
+        // Only reached by formats without arbitrary precision numbers,
+        // the shortest string that round trips is the intended value
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+            parse(&v.to_string()).map_err(|_| E::invalid_value(Unexpected::Float(v), &self))
+        }
+
+        // serde_json's arbitrary precision numbers are maps, which
+        // Decimal's own Deserialize understands
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Decimal, A::Error> {
+            <Decimal as Deserialize>::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+
+    fn parse(s: &str) -> Result<Decimal, DecUtilsError> {
+        ScientificFormatter::parse(s).or_else(|_| DecimalFormatter::usd().parse(s))
+    }
+
+    /// Serialize `v` as a string
+    pub fn serialize<S: Serializer>(v: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(v)
+    }
+
+    /// Deserialize a decimal from a number or string
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_any(LenientVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ::serde::{Deserialize, Serialize};
+    use rust_decimal::prelude::*;
+    use rust_decimal_macros::dec;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Row {
+        #[serde(with = "super::string")]
+        string: Decimal,
+        #[serde(with = "super::empty_as_none", default)]
+        empty: Option<Decimal>,
+        #[serde(with = "super::usd")]
+        usd: Decimal,
+        #[serde(with = "super::locale::fr_fr")]
+        fr: Decimal,
+        #[serde(with = "super::locale::en_in")]
+        en_in: Decimal,
+        #[serde(with = "super::lenient")]
+        lenient: Decimal,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values = [
+            dec!(0),
+            dec!(-1234567.125),
+            Decimal::MAX,
+            Decimal::MIN,
+            Decimal::new(1, 28),
+        ];
+        for v in values {
+            let row = Row {
+                string: v,
+                empty: Some(v),
+                usd: dec!(-1234.5),
+                fr: v,
+                en_in: v,
+                lenient: v,
+            };
+            let json = serde_json::to_string(&row).unwrap();
+            assert_eq!(serde_json::from_str::<Row>(&json).unwrap(), row);
+        }
+    }
+
+    #[test]
+    fn test_serialize() {
+        let row = Row {
+            string: dec!(-1234567.10),
+            empty: None,
+            usd: dec!(1234567.105),
+            fr: dec!(-1234567.10),
+            en_in: dec!(-1234567.10),
+            lenient: dec!(1e-7),
+        };
+        assert_eq!(
+            serde_json::to_value(&row).unwrap(),
+            serde_json::json!({
+                "string": "-1234567.10",
+                "empty": "",
+                "usd": "$1,234,567.10",
+                "fr": "-1\u{202f}234\u{202f}567,10",
+                "en_in": "-12,34,567.10",
+                "lenient": "0.0000001",
+            })
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let row: Row = serde_json::from_str(
+            r#"{
+                "string": " -1.50 ",
+                "empty": null,
+                "usd": "1,234.56 CR",
+                "fr": "1\u202f234,5",
+                "en_in": "1,00,000",
+                "lenient": "-2.5E3"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            row,
+            Row {
+                string: dec!(-1.50),
+                empty: None,
+                usd: dec!(-1234.56),
+                fr: dec!(1234.5),
+                en_in: dec!(100000),
+                lenient: dec!(-2500),
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let valid = serde_json::json!({
+            "string": "1",
+            "usd": "1",
+            "fr": "1",
+            "en_in": "1",
+            "lenient": 1,
+        });
+        assert!(serde_json::from_value::<Row>(valid.clone()).is_ok());
+        let invalid = [
+            ("string", serde_json::json!(1)),
+            ("string", serde_json::json!("1.5.5")),
+            ("string", serde_json::json!("79228162514264337593543950336")),
+            ("empty", serde_json::json!("abc")),
+            ("usd", serde_json::json!("€1")),
+            ("fr", serde_json::json!("1,234.5")),
+            ("en_in", serde_json::json!("1..5")),
+            ("lenient", serde_json::json!("")),
+            ("lenient", serde_json::json!(true)),
+            ("lenient", serde_json::json!(1e40)),
+        ];
+        for (field, value) in invalid {
+            let mut json = valid.clone();
+            json[field] = value;
+            assert!(
+                serde_json::from_value::<Row>(json.clone()).is_err(),
+                "{}",
+                json
+            );
+        }
+        let e = serde_json::from_str::<Row>(r#"{"string": "x"}"#).unwrap_err();
+        assert_eq!(e.to_string(), "invalid number \"x\" at line 1 column 14");
+    }
+}
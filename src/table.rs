@@ -0,0 +1,417 @@
+use rust_decimal::prelude::*;
+
+use crate::{DecUtilsError, DecimalFormatter};
+
+/// The output format of `Table::render`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TableStyle {
+    /// Aligned columns separated by spaces with a rule under the header
+    /// and above the totals
+    #[default]
+    Plain,
+
+    /// A GitHub flavored Markdown table with right aligned value columns
+    Markdown,
+
+    /// Comma separated values, fields are quoted as needed
+    Csv,
+}
+
+/// A value column of a `Table`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Column {
+    header: String,
+    formatter: DecimalFormatter,
+    total: bool,
+}
+
+impl Column {
+    /// A column with `header` whose cells are formatted by `formatter`,
+    /// included in the totals row
+    pub fn new(header: &str, formatter: DecimalFormatter) -> Self {
+        Self {
+            header: header.to_owned(),
+            formatter,
+            total: true,
+        }
+    }
+
+    /// Include this column in the totals row, true by default
+    pub fn total(mut self, total: bool) -> Self {
+        self.total = total;
+        self
+    }
+}
+
+/// Rows of labeled decimal cells rendered as plain text, Markdown or CSV
+///
+/// Each cell is formatted with its column's `DecimalFormatter`, None
+/// cells use the formatter's empty-value text. Totals are exact sums of
+/// the unrounded cell values.
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{Column, DecimalFormatter, Table, TableStyle};
+///
+/// let mut table = Table::new("Account")
+///     .column(Column::new("Debit", DecimalFormatter::usd()))
+///     .column(Column::new("Credit", DecimalFormatter::usd()))
+///     .totals("Total");
+/// table.push_row("Cash", [Some(dec!(1250)), None]);
+/// table.push_row("Revenue", [None, Some(dec!(1250))]);
+/// table.push_row("Fees", [dec!(12.5), dec!(0)]);
+///
+/// assert_eq!(
+///     table.render(TableStyle::Plain).unwrap(),
+///     "\
+/// Account      Debit     Credit
+/// -------  ---------  ---------
+/// Cash     $1,250.00
+/// Revenue             $1,250.00
+/// Fees        $12.50      $0.00
+/// -------  ---------  ---------
+/// Total    $1,262.50  $1,250.00
+/// "
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    label_header: String,
+    columns: Vec<Column>,
+    rows: Vec<(String, Vec<Option<Decimal>>)>,
+    totals_label: Option<String>,
+}
+
+impl Table {
+    /// An empty table whose first column, the row labels, has
+    /// `label_header`
+    pub fn new(label_header: &str) -> Self {
+        Self {
+            label_header: label_header.to_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Add a value column
+    pub fn column(mut self, column: Column) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Add a totals row labeled `label`
+    pub fn totals(mut self, label: &str) -> Self {
+        self.totals_label = Some(label.to_owned());
+        self
+    }
+
+    /// Add a row, missing cells are None and cells beyond the last
+    /// column are ignored
+    pub fn push_row<I>(&mut self, label: &str, cells: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<Option<Decimal>>,
+    {
+        let cells = cells.into_iter().map(Into::into).collect();
+        self.rows.push((label.to_owned(), cells));
+    }
+
+    /// The exact sum of each column, None for columns not totaled
+    ///
+    /// Returns `DecUtilsError::Overflow` if a sum is too large.
+    pub fn column_totals(&self) -> Result<Vec<Option<Decimal>>, DecUtilsError> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                if !column.total {
+                    return Ok(None);
+                }
+                self.rows
+                    .iter()
+                    .filter_map(|(_, cells)| cells.get(i).copied().flatten())
+                    .try_fold(Decimal::ZERO, |sum, v| sum.checked_add(v))
+                    .map(Some)
+                    .ok_or(DecUtilsError::Overflow)
+            })
+            .collect()
+    }
+
+    /// Render the table in `style`, every line ends with "\n"
+    ///
+    /// Returns an error if a column's formatter is invalid or a total
+    /// overflows.
+    pub fn render(&self, style: TableStyle) -> Result<String, DecUtilsError> {
+        let mut lines = Vec::with_capacity(self.rows.len() + 2);
+        lines.push(self.header());
+        for (label, cells) in &self.rows {
+            lines.push(self.format_row(label, |i| cells.get(i).copied().flatten())?);
+        }
+        let totals = match &self.totals_label {
+            Some(label) => {
+                let totals = self.column_totals()?;
+                Some(self.format_row(label, |i| totals[i])?)
+            }
+            None => None,
+        };
+
+        Ok(match style {
+            TableStyle::Plain => plain(&lines, totals.as_ref()),
+            TableStyle::Markdown => markdown(&lines, totals.as_ref()),
+            TableStyle::Csv => csv(&lines, totals.as_ref()),
+        })
+    }
+
+    fn header(&self) -> Vec<String> {
+        std::iter::once(self.label_header.clone())
+            .chain(self.columns.iter().map(|c| c.header.clone()))
+            .collect()
+    }
+
+    fn format_row(
+        &self,
+        label: &str,
+        cell: impl Fn(usize) -> Option<Decimal>,
+    ) -> Result<Vec<String>, DecUtilsError> {
+        let mut row = Vec::with_capacity(self.columns.len() + 1);
+        row.push(label.to_owned());
+        for (i, column) in self.columns.iter().enumerate() {
+            row.push(column.formatter.try_format_option(cell(i))?);
+        }
+        Ok(row)
+    }
+}
+
+/// Width in characters of each column
+fn widths<'a>(rows: impl Iterator<Item = &'a Vec<String>>) -> Vec<usize> {
+    let mut widths = Vec::new();
+    for row in rows {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    widths
+}
+
+/// Push `cell` padded to `width`, labels are left aligned and values
+/// right aligned
+fn push_padded(s: &mut String, cell: &str, width: usize, left: bool) {
+    let fill = width.saturating_sub(cell.chars().count());
+    if !left {
+        s.extend(std::iter::repeat_n(' ', fill));
+    }
+    s.push_str(cell);
+    if left {
+        s.extend(std::iter::repeat_n(' ', fill));
+    }
+}
+
+fn plain(lines: &[Vec<String>], totals: Option<&Vec<String>>) -> String {
+    let widths = widths(lines.iter().chain(totals));
+    let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+
+    let mut s = String::new();
+    let mut push_line = |row: &[String]| {
+        // Blank trailing cells are left out, the fill inside the last
+        // cell is kept so decimal mark aligned columns still line up
+        let len = row.iter().rposition(|cell| !cell.trim().is_empty());
+        let len = len.map_or(0, |last| last + 1);
+        for (i, (cell, &width)) in row[..len].iter().zip(&widths).enumerate() {
+            if i != 0 {
+                s.push_str("  ");
+            }
+            if i == 0 && len == 1 {
+                s.push_str(cell);
+            } else {
+                push_padded(&mut s, cell, width, i == 0);
+            }
+        }
+        s.push('\n');
+    };
+    push_line(&lines[0]);
+    push_line(&rule);
+    for row in &lines[1..] {
+        push_line(row);
+    }
+    if let Some(totals) = totals {
+        push_line(&rule);
+        push_line(totals);
+    }
+    s
+}
+
+fn markdown(lines: &[Vec<String>], totals: Option<&Vec<String>>) -> String {
+    let escaped: Vec<Vec<String>> = lines
+        .iter()
+        .chain(totals)
+        .map(|row| row.iter().map(|cell| cell.replace('|', "\\|")).collect())
+        .collect();
+    let widths: Vec<usize> = widths(escaped.iter()).iter().map(|&w| w.max(3)).collect();
+
+    let mut s = String::new();
+    let mut push_line = |row: &[String]| {
+        for (i, (cell, &width)) in row.iter().zip(&widths).enumerate() {
+            s.push_str("| ");
+            push_padded(&mut s, cell, width, i == 0);
+            s.push(' ');
+        }
+        s.push_str("|\n");
+    };
+    push_line(&escaped[0]);
+    let rule: Vec<String> = widths
+        .iter()
+        .enumerate()
+        .map(|(i, &w)| match i {
+            0 => format!(":{}", "-".repeat(w - 1)),
+            _ => format!("{}:", "-".repeat(w - 1)),
+        })
+        .collect();
+    push_line(&rule);
+    for row in &escaped[1..] {
+        push_line(row);
+    }
+    s
+}
+
+fn csv(lines: &[Vec<String>], totals: Option<&Vec<String>>) -> String {
+    let mut s = String::new();
+    for row in lines.iter().chain(totals) {
+        for (i, cell) in row.iter().enumerate() {
+            if i != 0 {
+                s.push(',');
+            }
+            if cell.contains([',', '"', '\n', '\r']) {
+                s.push('"');
+                s.push_str(&cell.replace('"', "\"\""));
+                s.push('"');
+            } else {
+                s.push_str(cell);
+            }
+        }
+        s.push('\n');
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{Alignment, SignStyle};
+    use rust_decimal_macros::dec;
+
+    fn table() -> Table {
+        let mut t = Table::new("Item")
+            .column(Column::new("Qty", DecimalFormatter::separated(0)).total(false))
+            .column(Column::new(
+                "Amount",
+                DecimalFormatter::usd().sign_style(SignStyle::Parentheses),
+            ))
+            .totals("Total");
+        t.push_row("Widget | large", [dec!(1200), dec!(-1234.565)]);
+        t.push_row("Gadget, \"mini\"", [Some(dec!(3))]);
+        t.push_row("Fee", [None, Some(dec!(0.005))]);
+        t
+    }
+
+    #[test]
+    fn test_column_totals() {
+        assert_eq!(
+            table().column_totals(),
+            Ok(vec![None, Some(dec!(-1234.560))])
+        );
+
+        let mut t = Table::new("").column(Column::new("", DecimalFormatter::new()));
+        assert_eq!(t.column_totals(), Ok(vec![Some(dec!(0))]));
+        t.push_row("a", [Decimal::MAX]);
+        t.push_row("b", [dec!(1)]);
+        assert_eq!(t.column_totals(), Err(DecUtilsError::Overflow));
+        assert_eq!(
+            t.totals("Total").render(TableStyle::Csv),
+            Err(DecUtilsError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(
+            table().render(TableStyle::Plain).unwrap(),
+            "\
+Item              Qty       Amount
+--------------  -----  -----------
+Widget | large  1,200  ($1,234.56)
+Gadget, \"mini\"      3
+Fee                          $0.00
+--------------  -----  -----------
+Total                  ($1,234.56)
+"
+        );
+    }
+
+    #[test]
+    fn test_plain_decimal_mark() {
+        let f = DecimalFormatter::new()
+            .fraction_digits(0, 2)
+            .sign_style(SignStyle::Parentheses)
+            .width(8)
+            .align(Alignment::DecimalMark);
+        let mut t = Table::new("Item").column(Column::new("Amount", f));
+        t.push_row("a", [dec!(12.25)]);
+        t.push_row("b", [dec!(-1.5)]);
+        t.push_row("c", [dec!(100)]);
+        t.push_row("d", [None]);
+        let plain = t.render(TableStyle::Plain).unwrap();
+        assert_eq!(
+            plain.lines().collect::<Vec<_>>(),
+            [
+                "Item    Amount",
+                "----  --------",
+                "a       12.25 ",
+                "b       (1.5) ",
+                "c      100    ",
+                "d",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            table().render(TableStyle::Markdown).unwrap(),
+            "\
+| Item            |   Qty |      Amount |
+| :-------------- | ----: | ----------: |
+| Widget \\| large | 1,200 | ($1,234.56) |
+| Gadget, \"mini\"  |     3 |             |
+| Fee             |       |       $0.00 |
+| Total           |       | ($1,234.56) |
+"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            table().render(TableStyle::Csv).unwrap(),
+            "\
+Item,Qty,Amount
+Widget | large,\"1,200\",\"($1,234.56)\"
+\"Gadget, \"\"mini\"\"\",3,
+Fee,,$0.00
+Total,,\"($1,234.56)\"
+"
+        );
+    }
+
+    #[test]
+    fn test_invalid_formatter() {
+        let mut t = Table::new("").column(Column::new(
+            "",
+            DecimalFormatter::new().group_separator(Some('.')),
+        ));
+        t.push_row("a", [dec!(1)]);
+        assert!(t.render(TableStyle::Plain).is_err());
+    }
+}
@@ -0,0 +1,666 @@
+use rust_decimal::prelude::*;
+
+use crate::digits::Digits;
+use crate::RoundingMode;
+
+/// The names of a currency's units in a language
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnitWords {
+    /// The name of one major unit, e.g. "dollar"
+    pub major_singular: String,
+    /// The name of several major units, e.g. "dollars"
+    pub major_plural: String,
+    /// The name of one minor unit, e.g. "cent"
+    pub minor_singular: String,
+    /// The name of several minor units, e.g. "cents"
+    pub minor_plural: String,
+}
+
+impl UnitWords {
+    /// Unit names, e.g. "dollar", "dollars", "cent", "cents"
+    pub fn new(
+        major_singular: &str,
+        major_plural: &str,
+        minor_singular: &str,
+        minor_plural: &str,
+    ) -> Self {
+        Self {
+            major_singular: major_singular.to_owned(),
+            major_plural: major_plural.to_owned(),
+            minor_singular: minor_singular.to_owned(),
+            minor_plural: minor_plural.to_owned(),
+        }
+    }
+}
+
+/// The words of a language used by `AmountWords`
+///
+/// Implement this to spell amounts in another language, words are
+/// lowercase and `AmountWords` applies the capitalization.
+pub trait WordTable {
+    /// `n` in words, e.g. "one thousand two hundred thirty-four"
+    fn cardinal(&self, n: u128) -> String;
+
+    /// `n` in words when followed by a unit name, for languages where
+    /// that differs, e.g. the Spanish "veintiún dólares" or "un millón
+    /// de dólares"
+    fn cardinal_before_noun(&self, n: u128) -> String {
+        self.cardinal(n)
+    }
+
+    /// The word joining the major and minor amounts, e.g. "and"
+    fn and(&self) -> &str;
+
+    /// The word before negative amounts, e.g. "minus"
+    fn minus(&self) -> &str;
+
+    /// The default unit names, those of the US dollar
+    fn units(&self) -> UnitWords;
+}
+
+/// American English, e.g. "one thousand two hundred thirty-four"
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct English;
+
+const EN_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const EN_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+// Short scale names of 10^3, 10^6, ... 10^36, enough for any u128
+const EN_SCALES: [&str; 13] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+    "sextillion",
+    "septillion",
+    "octillion",
+    "nonillion",
+    "decillion",
+    "undecillion",
+];
+
+impl English {
+    fn push_below_thousand(words: &mut Vec<String>, n: usize) {
+        let (hundreds, rest) = (n / 100, n % 100);
+        if hundreds > 0 {
+            words.push(EN_ONES[hundreds].to_owned());
+            words.push("hundred".to_owned());
+        }
+        match rest {
+            0 => {}
+            1..=19 => words.push(EN_ONES[rest].to_owned()),
+            _ if rest % 10 == 0 => words.push(EN_TENS[rest / 10].to_owned()),
+            _ => words.push(format!("{}-{}", EN_TENS[rest / 10], EN_ONES[rest % 10])),
+        }
+    }
+}
+
+impl WordTable for English {
+    fn cardinal(&self, n: u128) -> String {
+        if n == 0 {
+            return EN_ONES[0].to_owned();
+        }
+        let mut groups = Vec::with_capacity(EN_SCALES.len());
+        let mut rest = n;
+        while rest > 0 {
+            groups.push((rest % 1000) as usize);
+            rest /= 1000;
+        }
+
+        let mut words = Vec::new();
+        for (scale, &group) in groups.iter().enumerate().rev() {
+            if group > 0 {
+                Self::push_below_thousand(&mut words, group);
+                if scale > 0 {
+                    words.push(EN_SCALES[scale].to_owned());
+                }
+            }
+        }
+        words.join(" ")
+    }
+
+    fn and(&self) -> &str {
+        "and"
+    }
+
+    fn minus(&self) -> &str {
+        "minus"
+    }
+
+    fn units(&self) -> UnitWords {
+        UnitWords::new("dollar", "dollars", "cent", "cents")
+    }
+}
+
+/// Spanish, e.g. "mil doscientos treinta y cuatro"
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Spanish;
+
+const ES_UNITS: [&str; 30] = [
+    "cero",
+    "uno",
+    "dos",
+    "tres",
+    "cuatro",
+    "cinco",
+    "seis",
+    "siete",
+    "ocho",
+    "nueve",
+    "diez",
+    "once",
+    "doce",
+    "trece",
+    "catorce",
+    "quince",
+    "dieciséis",
+    "diecisiete",
+    "dieciocho",
+    "diecinueve",
+    "veinte",
+    "veintiuno",
+    "veintidós",
+    "veintitrés",
+    "veinticuatro",
+    "veinticinco",
+    "veintiséis",
+    "veintisiete",
+    "veintiocho",
+    "veintinueve",
+];
+
+const ES_TENS: [&str; 10] = [
+    "",
+    "",
+    "",
+    "treinta",
+    "cuarenta",
+    "cincuenta",
+    "sesenta",
+    "setenta",
+    "ochenta",
+    "noventa",
+];
+
+const ES_HUNDREDS: [&str; 10] = [
+    "",
+    "ciento",
+    "doscientos",
+    "trescientos",
+    "cuatrocientos",
+    "quinientos",
+    "seiscientos",
+    "setecientos",
+    "ochocientos",
+    "novecientos",
+];
+
+// Long scale (singular, plural) names of 10^6, 10^12, ... 10^36
+const ES_SCALES: [(&str, &str); 7] = [
+    ("", ""),
+    ("millón", "millones"),
+    ("billón", "billones"),
+    ("trillón", "trillones"),
+    ("cuatrillón", "cuatrillones"),
+    ("quintillón", "quintillones"),
+    ("sextillón", "sextillones"),
+];
+
+impl Spanish {
+    // A final "uno" is shortened to "un" before a noun, `apocope`
+    fn push_below_thousand(words: &mut Vec<String>, n: usize, apocope: bool) {
+        let (hundreds, rest) = (n / 100, n % 100);
+        match (hundreds, rest) {
+            (0, _) => {}
+            (1, 0) => words.push("cien".to_owned()),
+            _ => words.push(ES_HUNDREDS[hundreds].to_owned()),
+        }
+        let one = if apocope { "un" } else { "uno" };
+        match rest {
+            0 => {}
+            1 => words.push(one.to_owned()),
+            21 if apocope => words.push("veintiún".to_owned()),
+            2..=29 => words.push(ES_UNITS[rest].to_owned()),
+            _ if rest % 10 == 0 => words.push(ES_TENS[rest / 10].to_owned()),
+            _ if rest % 10 == 1 => {
+                words.push(format!("{} y {}", ES_TENS[rest / 10], one));
+            }
+            _ => words.push(format!("{} y {}", ES_TENS[rest / 10], ES_UNITS[rest % 10])),
+        }
+    }
+
+    fn push_below_million(words: &mut Vec<String>, n: usize, apocope: bool) {
+        let (thousands, rest) = (n / 1000, n % 1000);
+        if thousands > 1 {
+            Self::push_below_thousand(words, thousands, true);
+        }
+        if thousands > 0 {
+            words.push("mil".to_owned());
+        }
+        Self::push_below_thousand(words, rest, apocope);
+    }
+
+    fn spell(n: u128, apocope: bool) -> String {
+        if n == 0 {
+            return ES_UNITS[0].to_owned();
+        }
+        let mut groups = Vec::with_capacity(ES_SCALES.len());
+        let mut rest = n;
+        while rest > 0 {
+            groups.push((rest % 1_000_000) as usize);
+            rest /= 1_000_000;
+        }
+
+        let mut words = Vec::new();
+        for (scale, &group) in groups.iter().enumerate().rev() {
+            match (scale, group) {
+                (_, 0) => {}
+                (0, _) => Self::push_below_million(&mut words, group, apocope),
+                (_, 1) => {
+                    words.push("un".to_owned());
+                    words.push(ES_SCALES[scale].0.to_owned());
+                }
+                _ => {
+                    Self::push_below_million(&mut words, group, true);
+                    words.push(ES_SCALES[scale].1.to_owned());
+                }
+            }
+        }
+        words.join(" ")
+    }
+}
+
+impl WordTable for Spanish {
+    fn cardinal(&self, n: u128) -> String {
+        Self::spell(n, false)
+    }
+
+    fn cardinal_before_noun(&self, n: u128) -> String {
+        // A number ending in a scale word takes "de" before the noun,
+        // "dos millones de dólares" but "dos millones cien dólares"
+        let words = Self::spell(n, true);
+        if n >= 1_000_000 && n.is_multiple_of(1_000_000) {
+            words + " de"
+        } else {
+            words
+        }
+    }
+
+    fn and(&self) -> &str {
+        "con"
+    }
+
+    fn minus(&self) -> &str {
+        "menos"
+    }
+
+    fn units(&self) -> UnitWords {
+        UnitWords::new("dólar", "dólares", "centavo", "centavos")
+    }
+}
+
+/// How the minor units of an amount are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FractionStyle {
+    /// As a fraction like on a check, "... and 56/100 dollars"
+    #[default]
+    Fraction,
+
+    /// In words, "... dollars and fifty-six cents"
+    Words,
+}
+
+/// The letter case of the words
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Capitalization {
+    /// "One thousand two hundred thirty-four"
+    #[default]
+    Sentence,
+
+    /// "one thousand two hundred thirty-four"
+    Lower,
+
+    /// "ONE THOUSAND TWO HUNDRED THIRTY-FOUR"
+    Upper,
+
+    /// "One Thousand Two Hundred Thirty-Four"
+    Title,
+}
+
+/// Spells out amounts in words for checks and payment advices
+///
+/// The amount is rounded to 2 decimal places using "Bankers Rounding",
+/// like `dec_to_usd_string`, unless configured otherwise.
+///
+/// # Example
+/// ```
+/// use rust_decimal_macros::dec;
+///
+/// use dec_utils::{AmountWords, Capitalization, FractionStyle, Spanish};
+///
+/// let f = AmountWords::new();
+/// assert_eq!(
+///     f.format(dec!(1234.56)),
+///     "One thousand two hundred thirty-four and 56/100 dollars"
+/// );
+///
+/// let f = f.fraction_style(FractionStyle::Words).capitalization(Capitalization::Upper);
+/// assert_eq!(f.format(dec!(21.01)), "TWENTY-ONE DOLLARS AND ONE CENT");
+///
+/// let f = AmountWords::with_words(Spanish).fraction_style(FractionStyle::Words);
+/// assert_eq!(
+///     f.format(dec!(1221.50)),
+///     "Mil doscientos veintiún dólares con cincuenta centavos"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AmountWords<W = English> {
+    words: W,
+    units: UnitWords,
+    dp: u32,
+    rounding: RoundingMode,
+    fraction_style: FractionStyle,
+    capitalization: Capitalization,
+}
+
+impl Default for AmountWords {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AmountWords {
+    /// US dollars in English, with the fraction style used on checks
+    pub fn new() -> Self {
+        Self::with_words(English)
+    }
+}
+
+impl<W: WordTable> AmountWords<W> {
+    /// Amounts in the language of `words` using its default unit names
+    pub fn with_words(words: W) -> Self {
+        Self {
+            units: words.units(),
+            words,
+            dp: 2,
+            rounding: RoundingMode::default(),
+            fraction_style: FractionStyle::default(),
+            capitalization: Capitalization::default(),
+        }
+    }
+
+    /// The unit names, e.g. for another currency
+    pub fn units(mut self, units: UnitWords) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Number of decimal places of the minor unit, 2 by default and 0
+    /// for a currency without one
+    pub fn dp(mut self, dp: u32) -> Self {
+        self.dp = dp;
+        self
+    }
+
+    /// The mode used when rounding to `dp` decimal places
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// How the minor units are written
+    pub fn fraction_style(mut self, fraction_style: FractionStyle) -> Self {
+        self.fraction_style = fraction_style;
+        self
+    }
+
+    /// The letter case of the result
+    pub fn capitalization(mut self, capitalization: Capitalization) -> Self {
+        self.capitalization = capitalization;
+        self
+    }
+
+    /// Spell out `v`
+    pub fn format(&self, v: Decimal) -> String {
+        let rounded = self.rounding.round_dp(v, self.dp);
+        let digits = Digits::new(rounded);
+        // A Decimal has at most 29 integral digits so this always fits
+        let major: u128 = digits.integral().parse().unwrap_or_default();
+        let mut fractional = digits.fractional().to_owned();
+        let dp = self.dp.min(28) as usize;
+        fractional.extend(std::iter::repeat_n(
+            '0',
+            dp.saturating_sub(fractional.len()),
+        ));
+        let minor: u128 = fractional.parse().unwrap_or_default();
+
+        let words = &self.words;
+        let units = &self.units;
+        let major_unit = |n: u128| match n {
+            1 => &units.major_singular,
+            _ => &units.major_plural,
+        };
+        let minor_unit = |n: u128| match n {
+            1 => &units.minor_singular,
+            _ => &units.minor_plural,
+        };
+
+        let mut s = String::new();
+        if rounded.is_sign_negative() && !rounded.is_zero() {
+            s.push_str(words.minus());
+            s.push(' ');
+        }
+        match self.fraction_style {
+            FractionStyle::Fraction if dp > 0 => {
+                s.push_str(&words.cardinal(major));
+                s.push_str(&format!(
+                    " {} {}/1{} {}",
+                    words.and(),
+                    fractional,
+                    "0".repeat(dp),
+                    units.major_plural
+                ));
+            }
+            FractionStyle::Words if major == 0 && minor > 0 => {
+                s.push_str(&words.cardinal_before_noun(minor));
+                s.push(' ');
+                s.push_str(minor_unit(minor));
+            }
+            _ => {
+                s.push_str(&words.cardinal_before_noun(major));
+                s.push(' ');
+                s.push_str(major_unit(major));
+                if minor > 0 {
+                    s.push_str(&format!(
+                        " {} {} {}",
+                        words.and(),
+                        words.cardinal_before_noun(minor),
+                        minor_unit(minor)
+                    ));
+                }
+            }
+        }
+
+        capitalize(&s, self.capitalization)
+    }
+}
+
+fn capitalize(s: &str, capitalization: Capitalization) -> String {
+    match capitalization {
+        Capitalization::Lower => s.to_owned(),
+        Capitalization::Upper => s.to_uppercase(),
+        Capitalization::Sentence => {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+        Capitalization::Title => {
+            let mut title = String::with_capacity(s.len());
+            let mut word_start = true;
+            for c in s.chars() {
+                if word_start {
+                    title.extend(c.to_uppercase());
+                } else {
+                    title.push(c);
+                }
+                word_start = c == ' ' || c == '-';
+            }
+            title
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_english_cardinal() {
+        let c = |n| English.cardinal(n);
+        assert_eq!(c(0), "zero");
+        assert_eq!(c(7), "seven");
+        assert_eq!(c(19), "nineteen");
+        assert_eq!(c(40), "forty");
+        assert_eq!(c(99), "ninety-nine");
+        assert_eq!(c(100), "one hundred");
+        assert_eq!(c(101), "one hundred one");
+        assert_eq!(c(1_000_000), "one million");
+        assert_eq!(c(1_002_003), "one million two thousand three");
+        assert_eq!(
+            c(u128::MAX),
+            "three hundred forty undecillion two hundred eighty-two decillion \
+             three hundred sixty-six nonillion nine hundred twenty octillion \
+             nine hundred thirty-eight septillion four hundred sixty-three sextillion \
+             four hundred sixty-three quintillion three hundred seventy-four quadrillion \
+             six hundred seven trillion four hundred thirty-one billion \
+             seven hundred sixty-eight million two hundred eleven thousand \
+             four hundred fifty-five"
+        );
+    }
+
+    #[test]
+    fn test_spanish_cardinal() {
+        let c = |n| Spanish.cardinal(n);
+        let b = |n| Spanish.cardinal_before_noun(n);
+        assert_eq!(c(0), "cero");
+        assert_eq!(c(1), "uno");
+        assert_eq!(b(1), "un");
+        assert_eq!(c(16), "dieciséis");
+        assert_eq!(c(21), "veintiuno");
+        assert_eq!(b(21), "veintiún");
+        assert_eq!(c(31), "treinta y uno");
+        assert_eq!(b(31), "treinta y un");
+        assert_eq!(c(100), "cien");
+        assert_eq!(c(101), "ciento uno");
+        assert_eq!(c(500), "quinientos");
+        assert_eq!(c(1000), "mil");
+        assert_eq!(c(21_000), "veintiún mil");
+        assert_eq!(c(101_000), "ciento un mil");
+        assert_eq!(c(1_000_000), "un millón");
+        assert_eq!(b(1_000_000), "un millón de");
+        assert_eq!(b(1_000_001), "un millón un");
+        assert_eq!(c(2_000_000), "dos millones");
+        assert_eq!(c(1_000_000_000), "mil millones");
+        assert_eq!(c(21_000_001), "veintiún millones uno");
+        assert_eq!(c(1_000_000_000_000), "un billón");
+        assert_eq!(
+            c(1_234_567),
+            "un millón doscientos treinta y cuatro mil quinientos sesenta y siete"
+        );
+    }
+
+    #[test]
+    fn test_fraction_style() {
+        let f = AmountWords::new();
+        assert_eq!(f.format(dec!(0)), "Zero and 00/100 dollars");
+        assert_eq!(f.format(dec!(1)), "One and 00/100 dollars");
+        assert_eq!(f.format(dec!(0.125)), "Zero and 12/100 dollars");
+        assert_eq!(f.format(dec!(-0.001)), "Zero and 00/100 dollars");
+        assert_eq!(f.format(dec!(-5.5)), "Minus five and 50/100 dollars");
+        assert_eq!(
+            f.format(Decimal::MAX).split(" and ").last(),
+            Some("00/100 dollars")
+        );
+        let f = f.dp(3).rounding(RoundingMode::ToZero);
+        assert_eq!(f.format(dec!(1.9999)), "One and 999/1000 dollars");
+        let f = f.dp(0);
+        assert_eq!(f.format(dec!(1.9)), "One dollar");
+    }
+
+    #[test]
+    fn test_words_style() {
+        let f = AmountWords::new().fraction_style(FractionStyle::Words);
+        assert_eq!(f.format(dec!(0)), "Zero dollars");
+        assert_eq!(f.format(dec!(1)), "One dollar");
+        assert_eq!(f.format(dec!(0.01)), "One cent");
+        assert_eq!(f.format(dec!(0.56)), "Fifty-six cents");
+        assert_eq!(
+            f.format(dec!(1234.56)),
+            "One thousand two hundred thirty-four dollars and fifty-six cents"
+        );
+        let f = f.units(UnitWords::new("euro", "euros", "cent", "cents"));
+        assert_eq!(f.format(dec!(2.01)), "Two euros and one cent");
+    }
+
+    #[test]
+    fn test_spanish() {
+        let f = AmountWords::with_words(Spanish);
+        assert_eq!(
+            f.format(dec!(1234.56)),
+            "Mil doscientos treinta y cuatro con 56/100 dólares"
+        );
+        let f = f.fraction_style(FractionStyle::Words);
+        assert_eq!(f.format(dec!(1)), "Un dólar");
+        assert_eq!(f.format(dec!(0.01)), "Un centavo");
+        assert_eq!(f.format(dec!(-1_000_000)), "Menos un millón de dólares");
+        assert_eq!(f.format(dec!(2_000_000)), "Dos millones de dólares");
+        assert_eq!(
+            f.format(dec!(1_000_000_000.5)),
+            "Mil millones de dólares con cincuenta centavos"
+        );
+        assert_eq!(f.format(dec!(2_000_100)), "Dos millones cien dólares");
+    }
+
+    #[test]
+    fn test_capitalization() {
+        let v = dec!(41.5);
+        let f = |c| AmountWords::new().capitalization(c).format(v);
+        assert_eq!(f(Capitalization::Sentence), "Forty-one and 50/100 dollars");
+        assert_eq!(f(Capitalization::Lower), "forty-one and 50/100 dollars");
+        assert_eq!(f(Capitalization::Upper), "FORTY-ONE AND 50/100 DOLLARS");
+        assert_eq!(f(Capitalization::Title), "Forty-One And 50/100 Dollars");
+        let f = AmountWords::with_words(Spanish).capitalization(Capitalization::Upper);
+        assert_eq!(f.format(dec!(16)), "DIECISÉIS CON 00/100 DÓLARES");
+    }
+}